@@ -1,3 +1,8 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    reflect::{TypePath, TypeUuid},
+    utils::BoxedFuture,
+};
 use bevy_asset_loader::{
     asset_collection::AssetCollection,
     loading_state::{LoadingState, LoadingStateAppExt},
@@ -10,14 +15,22 @@ pub struct GameAssetsPlugin;
 
 impl Plugin for GameAssetsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_loading_state(
-            LoadingState::new(GameState::Loading).continue_to_state(GameState::Playing),
-        )
-        .add_systems(
-            OnExit(GameState::Loading),
-            (create_lasers, create_skeletons),
-        )
-        .add_collection_to_loading_state::<_, GameAssets>(GameState::Loading);
+        app.add_asset::<RonData>()
+            .init_asset_loader::<RonDataLoader>()
+            .add_loading_state(
+                LoadingState::new(GameState::Loading).continue_to_state(GameState::MainMenu),
+            )
+            .add_systems(
+                OnExit(GameState::Loading),
+                (
+                    create_lasers,
+                    create_skeletons,
+                    create_upgrade_catalogue,
+                    create_ship_class_catalogue,
+                    create_hull_catalogue,
+                ),
+            )
+            .add_collection_to_loading_state::<_, GameAssets>(GameState::Loading);
     }
 }
 
@@ -40,6 +53,13 @@ pub struct GameAssets {
     #[asset(texture_atlas(tile_size_x = 16., tile_size_y = 16., columns = 8, rows = 1))]
     #[asset(path = "sprites/Upgrades.png")]
     pub upgrades: Handle<TextureAtlas>,
+    // Game data, parsed once loading is done.
+    #[asset(path = "upgrades.ron")]
+    pub upgrade_catalogue: Handle<RonData>,
+    #[asset(path = "ship_classes.ron")]
+    pub ship_classes: Handle<RonData>,
+    #[asset(path = "hulls.ron")]
+    pub hulls: Handle<RonData>,
     // Need to load atlas and jsons, then create skeletons.
     #[asset(path = "spines/player_ship.atlas")]
     pub player_ship_atlas: Handle<Atlas>,
@@ -95,12 +115,53 @@ pub struct GameAssets {
     pub deploy_jammer: Handle<AudioSource>,
 }
 
+/// The raw text of a RON data file. Every data file shares the `.ron` extension,
+/// so they are loaded as text and parsed into their own types after loading.
+#[derive(TypeUuid, TypePath)]
+#[uuid = "bbb0bc96-8dd7-4d67-9401-fea5a0582974"]
+pub struct RonData(pub String);
+
+#[derive(Default)]
+pub struct RonDataLoader;
+
+impl AssetLoader for RonDataLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let contents = String::from_utf8(bytes.to_vec())?;
+            load_context.set_default_asset(LoadedAsset::new(RonData(contents)));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
 #[derive(Resource)]
 pub struct Skeletons {
     pub player_ship: Handle<SkeletonData>,
     pub cargo_ship: Handle<SkeletonData>,
 }
 
+impl Skeletons {
+    /// Every name `get` knows, for checking catalogues as they load.
+    pub const NAMES: [&'static str; 2] = ["player_ship", "cargo_ship"];
+
+    /// Looks a skeleton up by the name ship classes use for it.
+    pub fn get(&self, name: &str) -> Option<&Handle<SkeletonData>> {
+        match name {
+            "player_ship" => Some(&self.player_ship),
+            "cargo_ship" => Some(&self.cargo_ship),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Resource)]
 pub struct Lasers {
     pub player_laser_mesh: Handle<Mesh>,
@@ -108,6 +169,11 @@ pub struct Lasers {
     // Cargo lasers!
     pub cargo_ship_laser_mesh: Handle<Mesh>,
     pub cargo_ship_laser_material: Handle<ColorMaterial>,
+    // Secondary weapons.
+    pub missile_mesh: Handle<Mesh>,
+    pub missile_material: Handle<ColorMaterial>,
+    pub mine_mesh: Handle<Mesh>,
+    pub mine_material: Handle<ColorMaterial>,
     // Jammer pixels (not lasers, oh well)!
     pub jammer_mesh: Handle<Mesh>,
     pub jammer_material: Handle<ColorMaterial>,
@@ -139,6 +205,39 @@ fn create_skeletons(
     });
 }
 
+fn create_upgrade_catalogue(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    ron_data: Res<Assets<RonData>>,
+) {
+    let contents = &ron_data.get(&assets.upgrade_catalogue).unwrap().0;
+    let catalogue = UpgradeCatalogue::from_ron(contents)
+        .unwrap_or_else(|err| panic!("Invalid assets/upgrades.ron: {}", err));
+    commands.insert_resource(catalogue);
+}
+
+fn create_ship_class_catalogue(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    ron_data: Res<Assets<RonData>>,
+) {
+    let contents = &ron_data.get(&assets.ship_classes).unwrap().0;
+    let catalogue = ShipClassCatalogue::from_ron(contents)
+        .unwrap_or_else(|err| panic!("Invalid assets/ship_classes.ron: {}", err));
+    commands.insert_resource(catalogue);
+}
+
+fn create_hull_catalogue(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    ron_data: Res<Assets<RonData>>,
+) {
+    let contents = &ron_data.get(&assets.hulls).unwrap().0;
+    let catalogue = HullCatalogue::from_ron(contents)
+        .unwrap_or_else(|err| panic!("Invalid assets/hulls.ron: {}", err));
+    commands.insert_resource(catalogue);
+}
+
 fn create_lasers(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
@@ -152,6 +251,12 @@ fn create_lasers(
     let cargo_ship_laser_material =
         materials.add(ColorMaterial::from(Color::rgba(7.5, 7.5, 0.0, 15.0)));
 
+    let missile_mesh = meshes.add(Mesh::from(shape::Quad::new(Vec2::new(10., 4.))));
+    let missile_material = materials.add(ColorMaterial::from(Color::rgba(10.0, 4.0, 1.0, 10.0)));
+
+    let mine_mesh = meshes.add(Mesh::from(shape::Quad::new(Vec2::new(8., 8.))));
+    let mine_material = materials.add(ColorMaterial::from(Color::rgba(6.0, 0.5, 0.5, 5.0)));
+
     let jammer_mesh = meshes.add(Mesh::from(shape::Quad::new(Vec2::new(2., 2.))));
     let jammer_material = materials.add(ColorMaterial::from(Color::rgba(3.0, 3.0, 0.0, 1.0)));
 
@@ -163,6 +268,10 @@ fn create_lasers(
         player_laser_material,
         cargo_ship_laser_mesh,
         cargo_ship_laser_material,
+        missile_mesh,
+        missile_material,
+        mine_mesh,
+        mine_material,
         jammer_mesh,
         jammer_material,
         star_mesh,
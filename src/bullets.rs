@@ -1,61 +1,213 @@
-use bevy::audio;
+use bevy::sprite::Mesh2dHandle;
 
-use crate::prelude::*;
+use crate::{
+    damage::{Damage, DamageType},
+    effects::SoundEffect,
+    fighters::Fighter,
+    patrols::Piracy,
+    physics::{PhysicsSet, Ram},
+    prelude::*,
+    projectiles::{RetireProjectile, SpawnLaser},
+};
 
 pub struct BulletsPlugin;
 
 impl Plugin for BulletsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (
-                cargo_ship_damage_system.run_if(not(in_state(GameState::Loading))),
-                player_ship_damage_system.run_if(not(in_state(GameState::Loading))),
-            ),
-        );
+        app.init_resource::<BulletHits>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    cargo_ship_damage_system.run_if(not(in_state(GameState::Loading))),
+                    player_ship_damage_system.run_if(not(in_state(GameState::Loading))),
+                    fighter_damage_system.run_if(not(in_state(GameState::Loading))),
+                    ram_damage_system.run_if(not(in_state(GameState::Loading))),
+                )
+                    .after(pick_bullet_hits)
+                    .in_set(PhysicsSet::Resolve),
+            )
+            .add_systems(
+                FixedUpdate,
+                pick_bullet_hits
+                    .run_if(not(in_state(GameState::Loading)))
+                    .in_set(PhysicsSet::Resolve),
+            )
+            .add_systems(Update, dress_lasers.run_if(resource_exists::<Lasers>()));
     }
 }
 
-#[derive(Component, PartialEq, Eq)]
-pub enum Bullet {
+/// Which side fired a projectile, and so what it can hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Owner {
     Player,
     Enemy,
 }
 
-const PLAYER_DAMAGE: f32 = 5.0;
+/// Anything fired that does `Damage` when it hits.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Bullet {
+    pub owner: Owner,
+}
+
+/// Marks a bullet as a plain laser bolt, drawn by `dress_lasers`.
+#[derive(Component, Debug)]
+pub struct Laser;
+
+/// The first thing each bullet touched this tick. Bullets are only retired once the tick's
+/// commands run, so this keeps one from damaging everything else it passes through too.
+#[derive(Resource, Debug, Default)]
+pub struct BulletHits(HashMap<Entity, (Entity, f32)>);
+
+impl BulletHits {
+    pub fn first_hit(&self, bullet: Entity) -> Option<Entity> {
+        self.0.get(&bullet).map(|(target, _)| *target)
+    }
+}
+
+/// What cargo ship turrets hit with.
+pub const ENEMY_LASER: Damage = Damage {
+    amount: 5.0,
+    damage_type: DamageType::Energy,
+    penetration: 0.0,
+};
+
+impl Bullet {
+    pub fn player() -> Self {
+        Self {
+            owner: Owner::Player,
+        }
+    }
+
+    pub fn enemy() -> Self {
+        Self {
+            owner: Owner::Enemy,
+        }
+    }
+
+    pub fn collision_layers(&self) -> CollisionLayers {
+        match self.owner {
+            Owner::Player => CollisionLayers::new(
+                CollisionLayers::PLAYER_BULLET,
+                CollisionLayers::CARGO_SHIP | CollisionLayers::FIGHTER,
+            ),
+            Owner::Enemy => {
+                CollisionLayers::new(CollisionLayers::ENEMY_BULLET, CollisionLayers::PLAYER)
+            }
+        }
+    }
+}
+
+/// Fires a laser bolt, pooled if possible. New ones have no visuals until `dress_lasers`
+/// adds them when assets are loaded.
+pub fn spawn_laser(
+    commands: &mut Commands<'_, '_>,
+    transform: Transform,
+    inertia: InertiaVolume,
+    bullet: Bullet,
+    damage: Damage,
+) {
+    commands.add(SpawnLaser {
+        transform,
+        inertia,
+        bullet,
+        damage,
+    });
+}
+
+pub fn dress_lasers(
+    mut commands: Commands,
+    bullets: Query<(Entity, &Bullet), (Added<Bullet>, With<Laser>)>,
+    lasers: Res<Lasers>,
+) {
+    for (entity, bullet) in bullets.iter() {
+        let (mesh, material) = match bullet.owner {
+            Owner::Player => (
+                lasers.player_laser_mesh.clone(),
+                lasers.player_laser_material.clone(),
+            ),
+            Owner::Enemy => (
+                lasers.cargo_ship_laser_mesh.clone(),
+                lasers.cargo_ship_laser_material.clone(),
+            ),
+        };
+        commands.add(InsertSafe {
+            entity,
+            bundle: (Mesh2dHandle(mesh), material),
+        });
+    }
+}
+
+fn pick_bullet_hits(
+    mut collisions: EventReader<Collision>,
+    bullets: Query<(), With<Bullet>>,
+    mut hits: ResMut<BulletHits>,
+) {
+    hits.0.clear();
+    for collision in collisions.iter() {
+        let (bullet, target) = if bullets.contains(collision.e0) {
+            (collision.e0, collision.e1)
+        } else if bullets.contains(collision.e1) {
+            (collision.e1, collision.e0)
+        } else {
+            continue;
+        };
+        let first = hits
+            .0
+            .entry(bullet)
+            .or_insert((target, collision.time_of_impact));
+        if collision.time_of_impact < first.1 {
+            *first = (target, collision.time_of_impact);
+        }
+    }
+}
+
+// A section takes 15 hits, so shooting one up is almost as bad as destroying it.
+const HIT_SEVERITY: f32 = 0.05;
 
 fn cargo_ship_damage_system(
     mut commands: Commands,
     mut collisions: EventReader<Collision>,
-    player_bullets: Query<(Entity, &Bullet)>,
+    player_bullets: Query<(Entity, &Bullet, &Damage)>,
     cargo_sections: Query<(&Parent, &CargoSection)>,
-    mut cargo_ship: Query<(&mut CargoShip, &mut Spine)>,
-    game_assets: Res<GameAssets>,
+    mut cargo_ship: Query<(&mut CargoShip, Option<&mut Spine>)>,
+    mut sound_effects: EventWriter<SoundEffect>,
+    mut piracy: EventWriter<Piracy>,
+    hits: Res<BulletHits>,
 ) {
     for collision in collisions.iter() {
-        if let Ok((bullet_entity, bullet)) = player_bullets.get(collision.e0) {
-            if let Ok((cargo_ship_ref, cargo_section)) = cargo_sections.get(collision.e1) {
-                if bullet == &Bullet::Player {
-                    if let Ok((mut cargo_ship, mut ship_skeleton)) =
+        if let Some((bullet_entity, section_entity)) =
+            collision.matching(&player_bullets, &cargo_sections)
+        {
+            if hits.first_hit(bullet_entity) != Some(section_entity) {
+                continue;
+            }
+            let (bullet_entity, bullet, damage) = player_bullets.get(bullet_entity).unwrap();
+            if let Ok((cargo_ship_ref, cargo_section)) = cargo_sections.get(section_entity) {
+                if bullet.owner == Owner::Player {
+                    if let Ok((mut cargo_ship, m_ship_skeleton)) =
                         cargo_ship.get_mut(**cargo_ship_ref)
                     {
-                        unsafe {
-                            ship_skeleton
-                                .animation_state
-                                .set_animation_by_name_unchecked(
-                                    cargo_section.index,
-                                    cargo_section.hit_animation,
-                                    false,
-                                );
+                        let hit_animation =
+                            &cargo_ship.class.sections[cargo_section.index].hit_animation;
+                        if let (Some(mut ship_skeleton), Some(hit_animation)) =
+                            (m_ship_skeleton, hit_animation)
+                        {
+                            unsafe {
+                                ship_skeleton
+                                    .animation_state
+                                    .set_animation_by_name_unchecked(
+                                        cargo_section.index,
+                                        hit_animation,
+                                        false,
+                                    );
+                            }
                         }
-                        commands.spawn(AudioBundle {
-                            source: game_assets.cargo_ship_section_hit.clone(),
-                            settings: PlaybackSettings::DESPAWN,
+                        sound_effects.send(SoundEffect::CargoShipSectionHit);
+                        commands.add(RetireProjectile(bullet_entity));
+                        cargo_ship.damage_section(cargo_section.index, damage);
+                        piracy.send(Piracy {
+                            severity: HIT_SEVERITY,
                         });
-                        if let Some(mut bullet_entity) = commands.get_entity(bullet_entity) {
-                            bullet_entity.despawn();
-                        }
-                        cargo_ship.damage_section(cargo_section.index, PLAYER_DAMAGE);
                     }
                 }
             }
@@ -63,41 +215,137 @@ fn cargo_ship_damage_system(
     }
 }
 
-const ENEMY_DAMAGE: f32 = 5.0;
-
 fn player_ship_damage_system(
     mut commands: Commands,
     mut collisions: EventReader<Collision>,
-    enemy_bullets: Query<(Entity, &Bullet)>,
-    mut players: Query<(&mut Player, &mut Spine)>,
-    game_assets: Res<GameAssets>,
+    enemy_bullets: Query<(Entity, &Bullet, &Damage)>,
+    mut players: Query<(&mut Player, Option<&mut Spine>)>,
+    mut sound_effects: EventWriter<SoundEffect>,
+    hits: Res<BulletHits>,
 ) {
     for collision in collisions.iter() {
-        if let Ok((bullet_entity, bullet)) = enemy_bullets.get(collision.e0) {
-            if bullet == &Bullet::Enemy {
-                if let Ok((mut player, mut ship_skeleton)) = players.get_mut(collision.e1) {
-                    unsafe {
-                        ship_skeleton
-                            .animation_state
-                            .set_animation_by_name_unchecked(0, "hit", false);
-                    }
-                    if let Some(mut bullet_entity) = commands.get_entity(bullet_entity) {
-                        bullet_entity.despawn();
+        if let Some((bullet_entity, player_entity)) = collision.matching(&enemy_bullets, &players) {
+            if hits.first_hit(bullet_entity) != Some(player_entity) {
+                continue;
+            }
+            let (bullet_entity, bullet, damage) = enemy_bullets.get(bullet_entity).unwrap();
+            if bullet.owner == Owner::Enemy {
+                if let Ok((mut player, m_ship_skeleton)) = players.get_mut(player_entity) {
+                    if let Some(mut ship_skeleton) = m_ship_skeleton {
+                        unsafe {
+                            ship_skeleton
+                                .animation_state
+                                .set_animation_by_name_unchecked(0, "hit", false);
+                        }
                     }
+                    commands.add(RetireProjectile(bullet_entity));
                     if player.shields > 0. {
-                        commands.spawn(AudioBundle {
-                            source: game_assets.player_shield_hit.clone(),
-                            settings: PlaybackSettings::DESPAWN,
-                        });
+                        sound_effects.send(SoundEffect::PlayerShieldHit);
                     } else {
-                        commands.spawn(AudioBundle {
-                            source: game_assets.player_hull_hit.clone(),
-                            settings: PlaybackSettings::DESPAWN,
-                        });
+                        sound_effects.send(SoundEffect::PlayerHullHit);
                     }
-                    player.take_damage(ENEMY_DAMAGE);
+                    player.take_damage(damage);
                 }
             }
         }
     }
 }
+
+fn fighter_damage_system(
+    mut commands: Commands,
+    mut collisions: EventReader<Collision>,
+    player_bullets: Query<(&Bullet, &Damage)>,
+    mut fighters: Query<&mut Fighter>,
+    mut sound_effects: EventWriter<SoundEffect>,
+    hits: Res<BulletHits>,
+) {
+    for collision in collisions.iter() {
+        if let Some((bullet_entity, fighter_entity)) =
+            collision.matching(&player_bullets, &fighters)
+        {
+            if hits.first_hit(bullet_entity) != Some(fighter_entity) {
+                continue;
+            }
+            let Ok((bullet, damage)) = player_bullets.get(bullet_entity) else {
+                continue;
+            };
+            if bullet.owner != Owner::Player {
+                continue;
+            }
+            if let Ok(mut fighter) = fighters.get_mut(fighter_entity) {
+                sound_effects.send(SoundEffect::CargoShipSectionHit);
+                commands.add(RetireProjectile(bullet_entity));
+                fighter.take_damage(damage);
+            }
+        }
+    }
+}
+
+// Hull damage per unit of impact energy, so a 300 u/s bump costs about 20 hull.
+const RAM_DAMAGE_PER_ENERGY: f32 = 0.0005;
+
+fn ram_damage_system(
+    mut rams: EventReader<Ram>,
+    mut players: Query<&mut Player>,
+    cargo_sections: Query<(&Parent, &CargoSection)>,
+    mut cargo_ships: Query<&mut CargoShip>,
+    mut sound_effects: EventWriter<SoundEffect>,
+) {
+    for ram in rams.iter() {
+        let damage = Damage::kinetic(ram.energy * RAM_DAMAGE_PER_ENERGY);
+        if let Ok(mut player) = players.get_mut(ram.entity) {
+            if player.shields > 0. {
+                sound_effects.send(SoundEffect::PlayerShieldHit);
+            } else {
+                sound_effects.send(SoundEffect::PlayerHullHit);
+            }
+            player.take_damage(&damage);
+        } else if let Ok((cargo_ship_ref, cargo_section)) = cargo_sections.get(ram.entity) {
+            if let Ok(mut cargo_ship) = cargo_ships.get_mut(**cargo_ship_ref) {
+                sound_effects.send(SoundEffect::CargoShipSectionHit);
+                cargo_ship.damage_section(cargo_section.index, &damage);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod bullets_tests {
+    use super::*;
+    use crate::{test_harness::Simulation, trade_routes::spawn_cargo_ship};
+
+    #[test]
+    fn player_bullet_damages_section() {
+        let mut simulation = Simulation::new();
+        let cargo_ship = simulation.spawn(|commands| {
+            spawn_cargo_ship(
+                commands,
+                Transform::default(),
+                InertiaVolume::new(10000.0, 0.0),
+                CargoShip::new(&ShipClass::freighter(), Manifest::Ore, 1.0),
+            )
+        });
+        // Let the sections find their place in the world first.
+        simulation.step(1);
+        // Fire straight up into the first section, at (-144, -16).
+        let mut inertia = InertiaVolume::new(1.0, 1.0);
+        inertia.velocity = Vec2::new(0.0, 1000.0);
+        simulation.spawn(|commands| {
+            spawn_laser(
+                commands,
+                Transform::from_xyz(-144.0, -80.0, 0.0),
+                inertia,
+                Bullet::player(),
+                Player::new().laser_damage(),
+            )
+        });
+        simulation.step(10);
+        let cargo_ship = simulation.get::<CargoShip>(cargo_ship).unwrap();
+        assert!(cargo_ship.aggressed);
+        assert_eq!(
+            cargo_ship.section_health(0),
+            75.0 - Player::new().laser_damage().amount
+        );
+        assert_eq!(cargo_ship.section_health(4), 75.0);
+    }
+}
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DamageType {
+    /// Anything that explodes, or rams.
+    Kinetic,
+    /// Lasers.
+    Energy,
+    /// Ion cannons. No different from a laser by default, but some hulls are hardened against it.
+    Ion,
+}
+
+/// What a projectile does to whatever it hits.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Damage {
+    pub amount: f32,
+    pub damage_type: DamageType,
+    /// The share of any resistance ignored, from 0 to 1.
+    pub penetration: f32,
+}
+
+/// The share of each type of damage stopped, from 0 to 1. Below 0 is a weakness.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Resistances {
+    #[serde(default)]
+    pub kinetic: f32,
+    #[serde(default)]
+    pub energy: f32,
+    #[serde(default)]
+    pub ion: f32,
+}
+
+impl Resistances {
+    pub fn get(&self, damage_type: DamageType) -> f32 {
+        match damage_type {
+            DamageType::Kinetic => self.kinetic,
+            DamageType::Energy => self.energy,
+            DamageType::Ion => self.ion,
+        }
+    }
+}
+
+impl Damage {
+    pub fn kinetic(amount: f32) -> Self {
+        Self {
+            amount,
+            damage_type: DamageType::Kinetic,
+            penetration: 0.,
+        }
+    }
+
+    /// How much gets through `resistances`.
+    pub fn against(&self, resistances: &Resistances) -> f32 {
+        let resistance = resistances.get(self.damage_type);
+        // Penetration only helps against resistance, it doesn't make weaknesses worse.
+        let resistance = if resistance > 0. {
+            resistance * (1. - self.penetration.clamp(0., 1.))
+        } else {
+            resistance
+        };
+        self.amount * (1. - resistance.min(1.))
+    }
+}
+
+#[cfg(test)]
+mod damage_tests {
+    use super::*;
+
+    #[test]
+    fn penetration_cuts_through_resistance() {
+        let armour = Resistances {
+            kinetic: 0.5,
+            energy: 0.0,
+            ion: -0.5,
+        };
+        assert_eq!(Damage::kinetic(10.0).against(&armour), 5.0);
+        let armour_piercing = Damage {
+            penetration: 0.5,
+            ..Damage::kinetic(10.0)
+        };
+        assert_eq!(armour_piercing.against(&armour), 7.5);
+        let ion = Damage {
+            amount: 10.0,
+            damage_type: DamageType::Ion,
+            penetration: 1.0,
+        };
+        assert_eq!(ion.against(&armour), 15.0);
+    }
+}
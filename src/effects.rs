@@ -0,0 +1,62 @@
+use crate::prelude::*;
+
+pub struct EffectsPlugin;
+
+impl Plugin for EffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SoundEffect>().add_systems(
+            Update,
+            play_sound_effects.run_if(resource_exists::<GameAssets>()),
+        );
+    }
+}
+
+/// A one-shot sound requested by the simulation. Gameplay systems send these
+/// instead of spawning audio, so they can run without any loaded assets.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundEffect {
+    CargoShipSectionHit,
+    CargoShipSectionDestroyed,
+    CargoShipHyperdrive,
+    CargoShipLaser,
+    PlayerShieldHit,
+    PlayerHullHit,
+    Pickup,
+    PickupXm,
+    Upgrade,
+    DeployJammer,
+    Fail,
+}
+
+impl SoundEffect {
+    fn source(&self, game_assets: &GameAssets) -> Handle<AudioSource> {
+        match self {
+            SoundEffect::CargoShipSectionHit => game_assets.cargo_ship_section_hit.clone(),
+            SoundEffect::CargoShipSectionDestroyed => {
+                game_assets.cargo_ship_section_destroyed.clone()
+            }
+            SoundEffect::CargoShipHyperdrive => game_assets.cargo_ship_hyperdrive.clone(),
+            SoundEffect::CargoShipLaser => game_assets.cargo_ship_laser.clone(),
+            SoundEffect::PlayerShieldHit => game_assets.player_shield_hit.clone(),
+            SoundEffect::PlayerHullHit => game_assets.player_hull_hit.clone(),
+            SoundEffect::Pickup => game_assets.pickup.clone(),
+            SoundEffect::PickupXm => game_assets.pickup_xm.clone(),
+            SoundEffect::Upgrade => game_assets.upgrade.clone(),
+            SoundEffect::DeployJammer => game_assets.deploy_jammer.clone(),
+            SoundEffect::Fail => game_assets.fail.clone(),
+        }
+    }
+}
+
+fn play_sound_effects(
+    mut commands: Commands,
+    mut sound_effects: EventReader<SoundEffect>,
+    game_assets: Res<GameAssets>,
+) {
+    for sound_effect in sound_effects.iter() {
+        commands.spawn(AudioBundle {
+            source: sound_effect.source(&game_assets),
+            settings: PlaybackSettings::DESPAWN,
+        });
+    }
+}
@@ -0,0 +1,222 @@
+use crate::{
+    damage::{DamageType, Resistances},
+    effects::SoundEffect,
+    patrols::Piracy,
+    physics::PhysicsSet,
+    prelude::*,
+};
+
+pub struct FightersPlugin;
+
+impl Plugin for FightersPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                reload_fighters_system
+                    .run_if(in_state(GameState::Playing))
+                    .in_set(PhysicsSet::Forces),
+                fighter_destruction_system.in_set(PhysicsSet::Resolve),
+            ),
+        );
+    }
+}
+
+/// A small combat ship, flown by patrols and escorts alike.
+#[derive(Component, Debug)]
+pub struct Fighter {
+    pub hull: f32,
+    /// How much piracy shooting this fighter down counts as.
+    pub infamy: f32,
+    pub armour: Resistances,
+    fire_cooldown: f32,
+}
+
+pub const FIGHTER_MASS: f32 = 2.0;
+pub const FIGHTER_RADIUS: f32 = 24.0;
+const FIGHTER_ENGINE: f32 = 1200.0;
+const FIGHTER_BRAKING: f32 = 800.0;
+const FIGHTER_TURN_RATE: f32 = 3.0;
+pub const FIGHTER_CRUISE_SPEED: f32 = 200.0;
+pub const FIGHTER_MAX_SPEED: f32 = 550.0;
+const FIGHTER_FIRE_RATE: f32 = 0.6;
+const FIGHTER_FIRE_LEEWAY: f32 = 0.15;
+pub const ATTACK_RANGE: f32 = 400.0;
+const FIGHTER_SALVAGE: usize = 3;
+// Fighters carry ion cannons. They hit like lasers, except against hulls hardened to ion.
+const FIGHTER_ION: Damage = Damage {
+    amount: 4.0,
+    damage_type: DamageType::Ion,
+    penetration: 0.0,
+};
+
+impl Fighter {
+    pub fn new(hull: f32, infamy: f32) -> Self {
+        Self {
+            hull,
+            infamy,
+            armour: Resistances::default(),
+            fire_cooldown: FIGHTER_FIRE_RATE,
+        }
+    }
+
+    pub fn take_damage(&mut self, damage: &Damage) {
+        self.hull -= damage.against(&self.armour);
+    }
+}
+
+/// Everything a fighter needs to fly and be shot at, without any visuals.
+pub fn fighter_bundle(
+    transform: Transform,
+    inertia: InertiaVolume,
+    fighter: Fighter,
+) -> impl Bundle {
+    (
+        SpatialBundle::from_transform(transform),
+        inertia,
+        Solid {
+            restitution: 0.3,
+            takes_ram_damage: false,
+        },
+        CollisionLayers::new(
+            CollisionLayers::FIGHTER,
+            CollisionLayers::PLAYER | CollisionLayers::PLAYER_BULLET,
+        ),
+        fighter,
+        Regional,
+    )
+}
+
+// There's no fighter skeleton, so fighters fly a tinted copy of the player's sprite.
+pub fn fighter_sprite(game_assets: &GameAssets, color: Color) -> (Sprite, Handle<Image>) {
+    (
+        Sprite {
+            color,
+            custom_size: Some(Vec2::splat(FIGHTER_RADIUS * 2.)),
+            ..Default::default()
+        },
+        game_assets.player.clone(),
+    )
+}
+
+pub fn turn_towards(inertia: &mut InertiaVolume, direction: Vec2, dt: f32) {
+    let difference = angle_difference(inertia.rotation(), direction.y.atan2(direction.x));
+    let turn = difference.clamp(-FIGHTER_TURN_RATE * dt, FIGHTER_TURN_RATE * dt);
+    inertia.apply_rotation_force(turn / dt, dt);
+}
+
+/// Points the fighter at `direction` and burns up to `speed`, killing any drift.
+pub fn fly_towards(inertia: &mut InertiaVolume, direction: Vec2, speed: f32, dt: f32) {
+    turn_towards(inertia, direction, dt);
+    if inertia.forward_speed() > speed {
+        inertia.apply_thrust_force_limited(-FIGHTER_ENGINE, 0., dt);
+    } else {
+        inertia.apply_thrust_force_limited(FIGHTER_ENGINE, speed, dt);
+    }
+    inertia.apply_thrust_braking(FIGHTER_BRAKING, dt);
+}
+
+/// Closes in on where the target will be.
+pub fn intercept(
+    position: Vec2,
+    inertia: &mut InertiaVolume,
+    (target_transform, target_inertia): (&Transform, &InertiaVolume),
+    dt: f32,
+) {
+    let delta = target_transform.translation.truncate() - position;
+    let intercept = aim_ahead_location(
+        position,
+        delta,
+        target_inertia.velocity - inertia.velocity,
+        FIGHTER_MAX_SPEED,
+    )
+    .unwrap_or(position + delta);
+    turn_towards(inertia, intercept - position, dt);
+    inertia.apply_thrust_force_limited(FIGHTER_ENGINE, FIGHTER_MAX_SPEED, dt);
+    inertia.apply_thrust_braking(FIGHTER_BRAKING, dt);
+}
+
+/// Holds off at a distance from the target, matching its speed and firing when lined up.
+pub fn attack(
+    fighter: &mut Fighter,
+    position: Vec2,
+    inertia: &mut InertiaVolume,
+    (target_transform, target_inertia): (&Transform, &InertiaVolume),
+    dt: f32,
+    commands: &mut Commands,
+    sound_effects: &mut EventWriter<SoundEffect>,
+) {
+    let delta = target_transform.translation.truncate() - position;
+    let aim = aim_ahead_location(
+        position,
+        delta,
+        target_inertia.velocity - inertia.velocity,
+        LASER_SPEED,
+    )
+    .unwrap_or(position + delta);
+    let aim_direction = aim - position;
+    turn_towards(inertia, aim_direction, dt);
+    let keep_up = target_inertia.velocity.length() + FIGHTER_CRUISE_SPEED;
+    if delta.length() > ATTACK_RANGE * 0.6 {
+        inertia.apply_thrust_force_limited(FIGHTER_ENGINE, keep_up, dt);
+    } else {
+        inertia.apply_thrust_force_limited(-FIGHTER_ENGINE, FIGHTER_CRUISE_SPEED, dt);
+    }
+    inertia.apply_thrust_braking(FIGHTER_BRAKING, dt);
+    let aim_rotation = aim_direction.y.atan2(aim_direction.x);
+    if fighter.fire_cooldown <= 0.
+        && rotations_match(aim_rotation, inertia.rotation(), FIGHTER_FIRE_LEEWAY)
+    {
+        fighter.fire_cooldown = FIGHTER_FIRE_RATE;
+        sound_effects.send(SoundEffect::CargoShipLaser);
+        let forward = Vec2::new(inertia.rotation().cos(), inertia.rotation().sin());
+        fire_laser_from_hardpoint(
+            (position + forward * FIGHTER_RADIUS).extend(10.0),
+            inertia.rotation(),
+            inertia,
+            commands,
+            Bullet::enemy(),
+            FIGHTER_ION,
+        );
+    }
+}
+
+fn reload_fighters_system(fixed_time: Res<FixedTime>, mut fighters: Query<&mut Fighter>) {
+    let dt = fixed_time.period.as_secs_f32();
+    for mut fighter in fighters.iter_mut() {
+        fighter.fire_cooldown -= dt;
+    }
+}
+
+fn fighter_destruction_system(
+    mut commands: Commands,
+    fighters: Query<(Entity, &Fighter, &Transform, &InertiaVolume)>,
+    mut rng: ResMut<GameRng>,
+    mut piracy: EventWriter<Piracy>,
+    mut sound_effects: EventWriter<SoundEffect>,
+) {
+    let rng = &mut rng.loot;
+    for (entity, fighter, transform, inertia) in fighters.iter() {
+        if fighter.hull > 0. {
+            continue;
+        }
+        sound_effects.send(SoundEffect::CargoShipSectionDestroyed);
+        piracy.send(Piracy {
+            severity: fighter.infamy,
+        });
+        for _ in 0..FIGHTER_SALVAGE {
+            let (mass, value) = Commodity::Scrap.roll_piece(rng, 1.0);
+            spawn_salvage(
+                transform.translation.x,
+                transform.translation.y,
+                inertia.velocity
+                    + Vec2::from_angle(rng.gen::<f32>() * PI * 2.) * (rng.gen::<f32>() * 80.0),
+                &mut commands,
+                Commodity::Scrap,
+                mass,
+                value,
+            );
+        }
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -1,19 +1,35 @@
 mod assets;
 mod bullets;
+mod damage;
+mod effects;
+mod fighters;
 mod game_over;
 mod game_state;
+mod heat;
 mod home;
+mod hulls;
 mod indicators;
 mod intro;
 mod jamming;
+mod main_menu;
+mod market;
+mod patrols;
 mod physics;
 mod pickups;
 mod player;
 mod prelude;
+mod projectiles;
+mod rng;
+mod save;
+mod secondary_weapons;
+mod shipyard;
 mod space_pixels;
+#[cfg(test)]
+mod test_harness;
 mod trade_routes;
 mod turrets;
 mod ui;
+mod upgrades;
 
 #[macro_use]
 extern crate lazy_static;
@@ -21,14 +37,25 @@ use assets::GameAssetsPlugin;
 use bevy::window::WindowResolution;
 use bevy_spine::SpinePlugin;
 use bullets::BulletsPlugin;
+use effects::EffectsPlugin;
+use fighters::FightersPlugin;
 use game_over::GameOverPlugin;
+use heat::HeatPlugin;
 use home::HomePlugin;
+use hulls::HullsPlugin;
 use indicators::IndicatorsPlugin;
 use intro::IntroPlugin;
 use jamming::JammingPlugin;
+use main_menu::MainMenuPlugin;
+use patrols::PatrolsPlugin;
 use physics::PhysicsPlugin;
 use pickups::PickupsPlugin;
 use player::PlayerPlugin;
+use projectiles::ProjectilesPlugin;
+use rng::GameRngPlugin;
+use save::SavePlugin;
+use secondary_weapons::SecondaryWeaponsPlugin;
+use shipyard::ShipyardPlugin;
 use space_pixels::SpacePixelsPlugin;
 use trade_routes::TradeRoutesPlugin;
 use ui::GameUiPlugin;
@@ -56,12 +83,25 @@ fn main() {
             TradeRoutesPlugin,
             IndicatorsPlugin,
             GameAssetsPlugin,
-            PhysicsPlugin,
+            PhysicsPlugin::default(),
             PickupsPlugin,
             PlayerPlugin,
             GameUiPlugin,
             SpacePixelsPlugin,
             HomePlugin,
         ))
+        .add_plugins((
+            GameRngPlugin,
+            MainMenuPlugin,
+            SavePlugin,
+            EffectsPlugin,
+            PatrolsPlugin,
+            FightersPlugin,
+            HeatPlugin,
+            ShipyardPlugin,
+            HullsPlugin,
+            SecondaryWeaponsPlugin,
+            ProjectilesPlugin,
+        ))
         .run();
 }
@@ -1,4 +1,10 @@
-use crate::{home::Career, prelude::*, trade_routes::spawn_starting_system, ui::UiState};
+use crate::{
+    heat::Heat,
+    home::{Career, LAST_DAY},
+    prelude::*,
+    trade_routes::spawn_starting_system,
+    ui::UiState,
+};
 
 pub struct GameOverPlugin;
 
@@ -37,8 +43,9 @@ fn check_player_retire_system(
     current_game_state: Res<State<GameState>>,
     mut next_game_state: ResMut<NextState<GameState>>,
 ) {
-    if *current_game_state == GameState::Playing || *current_game_state == GameState::Home {
-        if career.days_survived == 10 {
+    // On the last day, the hideout lets the player sell up before retiring.
+    if *current_game_state == GameState::Playing {
+        if career.days_survived == LAST_DAY {
             next_game_state.set(GameState::Retire);
         }
     }
@@ -99,17 +106,22 @@ fn play_again_system(
         )>,
     >,
     mut career: ResMut<Career>,
+    mut heat: ResMut<Heat>,
     mut next_game_state: ResMut<NextState<GameState>>,
     input: Res<Input<KeyCode>>,
     game_assets: Res<GameAssets>,
+    mut rng: ResMut<GameRng>,
 ) {
     if input.pressed(KeyCode::Escape) {
         next_game_state.set(GameState::Playing);
         for entity in despawned.iter() {
             commands.entity(entity).despawn_recursive();
         }
-        spawn_starting_system(commands, game_assets);
+        // A fixed seed replays the same run, otherwise roll a new one.
+        *rng = GameRng::from_environment();
+        spawn_starting_system(commands, game_assets, rng);
         *career = Career::default();
+        *heat = Heat::default();
         career.intro_stage = 1000;
     }
 }
@@ -3,6 +3,7 @@ use crate::prelude::*;
 #[derive(States, Debug, Hash, PartialEq, Eq, Clone, Copy)]
 pub enum GameState {
     Loading,
+    MainMenu,
     Playing,
     Hyperdrive,
     Home,
@@ -0,0 +1,65 @@
+use crate::{patrols::Piracy, prelude::*};
+
+pub struct HeatPlugin;
+
+impl Plugin for HeatPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Heat>()
+            .add_systems(Update, raise_heat_system);
+    }
+}
+
+pub const MAX_HEAT: f32 = 100.0;
+// Each day lying low at the hideout halves the heat.
+const DAILY_COOLING: f32 = 0.5;
+const HOT_ALERTNESS: f32 = 0.5;
+
+/// How wanted the player is across the whole galaxy. Every act of piracy raises it,
+/// and the rest of the galaxy gets more dangerous to match.
+#[derive(Resource, Debug, Default, Clone, Copy)]
+pub struct Heat {
+    pub level: f32,
+}
+
+impl Heat {
+    pub fn raise(&mut self, amount: f32) {
+        self.level = (self.level + amount).min(MAX_HEAT);
+    }
+
+    pub fn cool_off(&mut self) {
+        self.level *= DAILY_COOLING;
+    }
+
+    /// From 0 (nobody's looking) to 1 (everyone is).
+    pub fn alertness(&self) -> f32 {
+        (self.level / MAX_HEAT).clamp(0.0, 1.0)
+    }
+
+    pub fn is_hot(&self) -> bool {
+        self.alertness() >= HOT_ALERTNESS
+    }
+}
+
+fn raise_heat_system(mut heat: ResMut<Heat>, mut piracy: EventReader<Piracy>) {
+    for piracy in piracy.iter() {
+        heat.raise(piracy.severity);
+    }
+}
+
+#[cfg(test)]
+mod heat_tests {
+    use super::*;
+
+    #[test]
+    fn rises_and_cools() {
+        let mut heat = Heat::default();
+        heat.raise(60.0);
+        assert!(heat.is_hot());
+        heat.raise(60.0);
+        assert_eq!(heat.alertness(), 1.0);
+        heat.cool_off();
+        heat.cool_off();
+        assert!(!heat.is_hot());
+        assert_eq!(heat.level, 25.0);
+    }
+}
@@ -1,38 +1,40 @@
-use crate::{intro::INTRO_STAGES, prelude::*, ui::UiState};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    heat::Heat, intro::INTRO_STAGES, market::MarketPrices, prelude::*,
+    trade_routes::CurrentSystemRegion,
+};
+
+/// The day the player retires, if they live that long.
+pub const LAST_DAY: u32 = 10;
 
 pub struct HomePlugin;
 
 impl Plugin for HomePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<Career>()
-            .add_systems(OnEnter(GameState::Home), handle_go_home)
-            .add_systems(
-                Update,
-                (
-                    update_home_ui.run_if(not(in_state(GameState::Loading))),
-                    handle_home_input.run_if(in_state(GameState::Home)),
-                ),
-            );
+            .add_systems(OnEnter(GameState::Home), handle_go_home);
     }
 }
 
-#[derive(Resource)]
+#[derive(Resource, Clone, Serialize, Deserialize)]
 pub struct Career {
     pub earnings: f32,
-    pub last_repair_costs: f32,
-    pub last_upgrades: Vec<Upgrade>,
     pub days_survived: u32,
     pub intro_stage: usize,
+    /// The market's prices on every day of the career so far, ending with today's.
+    #[serde(default)]
+    pub price_history: Vec<MarketPrices>,
 }
 
 impl Default for Career {
     fn default() -> Self {
         Self {
             earnings: 0.0,
-            last_repair_costs: 0.0,
-            last_upgrades: Vec::new(),
             days_survived: 0,
             intro_stage: 0,
+            price_history: Vec::new(),
         }
     }
 }
@@ -41,95 +43,169 @@ impl Career {
     pub fn intro_complete(&self) -> bool {
         self.intro_stage > INTRO_STAGES
     }
+
+    pub fn prices(&self) -> MarketPrices {
+        self.price_history
+            .last()
+            .cloned()
+            .unwrap_or_else(MarketPrices::base)
+    }
+
+    pub fn yesterdays_prices(&self) -> Option<&MarketPrices> {
+        self.price_history.iter().rev().nth(1)
+    }
+
+    /// Sells everything in the hold of one commodity at today's prices, as adjusted by
+    /// the local fence.
+    pub fn sell_commodity(
+        &mut self,
+        player: &mut Player,
+        commodity: Commodity,
+        hideout: &Hideout,
+    ) -> f32 {
+        let quality = player.commodity_quality(commodity);
+        let mass = player.unload_commodity(commodity);
+        if self.price_history.is_empty() {
+            self.price_history.push(MarketPrices::base());
+        }
+        let proceeds = self.price_history.last_mut().unwrap().sell(commodity, mass)
+            * quality
+            * hideout.price_factor(commodity);
+        self.earnings += proceeds;
+        proceeds
+    }
 }
 
-#[derive(Component)]
-pub struct HomeInSystem;
+#[derive(Component, Debug)]
+pub struct HomeInSystem(pub Hideout);
+
+// What the other hideouts charge to buy into.
+const MIN_HIDEOUT_PRICE: f32 = 600.0;
+const MAX_HIDEOUT_PRICE: f32 = 1500.0;
+// The range of repair prices, relative to the ship's own cost per hull.
+const MIN_REPAIR_MULTIPLIER: f32 = 0.6;
+const MAX_REPAIR_MULTIPLIER: f32 = 1.4;
+// How much more a hideout's fence pays for the goods it's after, and how much less for
+// everything else.
+const BIAS_PREMIUM: f32 = 1.3;
+const BIAS_DISCOUNT: f32 = 0.9;
+
+/// A pirate hideout. Ending a day needs an unlocked one, and the career starts with one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hideout {
+    pub unlocked: bool,
+    /// What it costs to buy into, when it's locked.
+    pub price: f32,
+    /// The commodity the local fence is after.
+    pub market_bias: Commodity,
+    /// Scales the ship's `repair_cost_per_hull` here.
+    pub repair_multiplier: f32,
+    pub locker: Locker,
+}
+
+/// Whatever the player left at a hideout, until they come back for it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Locker {
+    pub exotic_material: f32,
+    /// Each material with the cargo space it takes up.
+    pub upgrade_materials: Vec<(Upgrade, f32)>,
+}
+
+impl Hideout {
+    /// The hideout a career starts out with, already paid for.
+    pub fn starting(rng: &mut impl Rng) -> Self {
+        Self {
+            unlocked: true,
+            price: 0.0,
+            market_bias: *Commodity::ALL.choose(rng).unwrap(),
+            repair_multiplier: 1.0,
+            locker: Locker::default(),
+        }
+    }
+
+    pub fn random(rng: &mut impl Rng) -> Self {
+        Self {
+            unlocked: false,
+            price: rng.gen_range(MIN_HIDEOUT_PRICE..MAX_HIDEOUT_PRICE).round(),
+            market_bias: *Commodity::ALL.choose(rng).unwrap(),
+            repair_multiplier: rng.gen_range(MIN_REPAIR_MULTIPLIER..MAX_REPAIR_MULTIPLIER),
+            locker: Locker::default(),
+        }
+    }
+
+    /// How this hideout's fence adjusts the market price of `commodity`.
+    pub fn price_factor(&self, commodity: Commodity) -> f32 {
+        if commodity == self.market_bias {
+            BIAS_PREMIUM
+        } else {
+            BIAS_DISCOUNT
+        }
+    }
+
+    pub fn repair_cost_per_hull(&self, player: &Player) -> f32 {
+        player.repair_cost_per_hull * self.repair_multiplier
+    }
+}
 
 pub fn spawn_home_in_system(
     location: Vec2,
+    hideout: Hideout,
     game_assets: &Res<GameAssets>,
     mut commands: &mut Commands,
-) {
+) -> Entity {
     let (indicator, indicator_text) = create_indicator_with_text(commands, game_assets, false);
-    commands.spawn((
-        TransformBundle::default(),
-        SystemLocation { location },
-        HomeInSystem,
-        DistantIndicator::new_system(indicator, indicator_text, Vec2::ZERO),
-    ));
+    commands
+        .spawn((
+            TransformBundle::default(),
+            SystemLocation { location },
+            HomeInSystem(hideout),
+            DistantIndicator::new_system(indicator, indicator_text, Vec2::ZERO),
+        ))
+        .id()
+}
+
+/// Starts a new day at the hideout. Selling, repairs and upgrades are left to the player
+/// at the shipyard.
+pub fn end_day(player: &mut Player, career: &mut Career, heat: &mut Heat, rng: &mut GameRng) {
+    player.shields = player.max_shields;
+    let prices = career.prices().next_day(&mut rng.market);
+    career.price_history.push(prices);
+    career.days_survived += 1;
+    heat.cool_off();
 }
 
+// Locked hideouts don't end the day until the player buys their way in.
 fn handle_go_home(
     mut players: Query<&mut Player>,
+    hideouts: Query<&HomeInSystem, With<CurrentSystemRegion>>,
     mut career: ResMut<Career>,
-    mut commands: Commands,
-    game_assets: Res<GameAssets>,
+    mut heat: ResMut<Heat>,
+    mut rng: ResMut<GameRng>,
 ) {
-    if players.is_empty() {
+    let (Ok(mut player), Ok(hideout)) = (players.get_single_mut(), hideouts.get_single()) else {
         return;
+    };
+    if hideout.0.unlocked {
+        end_day(&mut player, &mut career, &mut heat, &mut rng);
     }
-    let mut player = players.single_mut();
-    career.earnings += player.salvage_value;
-    player.salvage_mass = 0.0;
-    player.upgrade_mass = 0.0;
-    player.salvage_value = 0.0;
-
-    let repair_costs = player.get_repair_cost().min(career.earnings);
-    career.earnings -= repair_costs;
-    career.last_repair_costs = repair_costs;
-    player.repair(repair_costs);
-
-    career.last_upgrades.clear();
-    if !player.upgrade_materials.is_empty() {
-        commands.spawn(AudioBundle {
-            source: game_assets.upgrade.clone(),
-            settings: PlaybackSettings::DESPAWN,
-        });
-        for upgrade in player.upgrade_materials.drain(..).collect::<Vec<_>>() {
-            player.apply_upgrade(upgrade);
-            career.last_upgrades.push(upgrade);
-        }
-    }
-
-    career.days_survived += 1;
 }
 
-fn handle_home_input(mut next_state: ResMut<NextState<GameState>>, keys: Res<Input<KeyCode>>) {
-    if keys.just_pressed(KeyCode::Space) {
-        next_state.set(GameState::Playing);
-    }
-}
+#[cfg(test)]
+mod home_tests {
+    use super::*;
 
-fn update_home_ui(
-    game_state: Res<State<GameState>>,
-    mut ui_state: ResMut<UiState>,
-    career: Res<Career>,
-    mut texts: Query<&mut Text>,
-    mut visibility: Query<&mut Visibility>,
-) {
-    if *game_state == GameState::Home {
-        if let Ok(mut visible) = visibility.get_mut(ui_state.home_text) {
-            *visible = Visibility::Visible;
-        }
-        if let Ok(mut text) = texts.get_mut(ui_state.home_text) {
-            text.sections[1].value = format!("${:.0}", career.earnings);
-            text.sections[3].value = format!("${:.0}", career.last_repair_costs);
-
-            if career.last_upgrades.len() > 0 {
-                text.sections[4].value = "\n".to_string();
-                for upgrade in career.last_upgrades.iter() {
-                    text.sections[4].value =
-                        format!("{}\nUpgrade found: {}", text.sections[4].value, upgrade);
-                }
-            } else {
-                text.sections[4].value = "\n".to_string();
-            }
-            text.sections[5].value = format!("\n\n{} days left", 10 - career.days_survived);
-        }
-    } else {
-        if let Ok(mut visible) = visibility.get_mut(ui_state.home_text) {
-            *visible = Visibility::Hidden;
-        }
+    #[test]
+    fn richer_salvage_sells_for_more() {
+        let hideout = Hideout::starting(&mut GameRng::new(2444).world);
+        let sell = |value_modifier: f32| {
+            let mut rng = GameRng::new(2444);
+            let (mass, value) = Commodity::Ore.roll_piece(&mut rng.loot, value_modifier);
+            let mut player = Player::new();
+            player.load_commodity(Commodity::Ore, mass, value);
+            Career::default().sell_commodity(&mut player, Commodity::Ore, &hideout)
+        };
+        let (plain, rich) = (sell(1.0), sell(1.5));
+        assert!(plain > 0.0);
+        assert!((rich / plain - 1.5).abs() < 0.001);
     }
 }
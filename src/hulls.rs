@@ -0,0 +1,181 @@
+use anyhow::bail;
+use serde::Deserialize;
+
+use crate::{damage::Resistances, prelude::*, upgrades::PlayerStat};
+
+pub struct HullsPlugin;
+
+impl Plugin for HullsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (fit_player_hull, hide_unused_player_bones)
+                .chain()
+                .run_if(resource_exists::<HullCatalogue>()),
+        );
+    }
+}
+
+/// What every new career starts out flying.
+pub const STARTING_HULL: &str = "Raider";
+
+/// Every hull the player can fly, loaded from `assets/hulls.ron`.
+#[derive(Resource, Debug, Clone, Deserialize)]
+pub struct HullCatalogue {
+    pub hulls: Vec<HullClass>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HullClass {
+    pub id: String,
+    pub name: String,
+    /// Which of the `Skeletons` the ship is drawn with.
+    pub skeleton: String,
+    /// Skeleton bones this hull doesn't use, hidden every frame.
+    #[serde(default)]
+    pub hidden_bones: Vec<String>,
+    pub scale: f32,
+    pub mass: f32,
+    /// Collision radius, in world units.
+    pub radius: f32,
+    pub price: f32,
+    /// How many secondary weapons the hull can fit.
+    pub secondary_slots: usize,
+    /// Starting values for the ship's stats, before any upgrades.
+    pub stats: Vec<(PlayerStat, f32)>,
+    /// Replace the usual shield and hull resistances, if set.
+    #[serde(default)]
+    pub shield_resistances: Option<Resistances>,
+    #[serde(default)]
+    pub hull_resistances: Option<Resistances>,
+    pub turrets: Vec<HullTurret>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HullTurret {
+    pub bone: String,
+    /// Which way the turret points, in degrees anticlockwise from the nose.
+    pub facing: f32,
+    /// How far either side of `facing` the turret can fire, in degrees.
+    pub arc: f32,
+}
+
+impl HullTurret {
+    /// Whether the turret can fire at `aim_rotation`, relative to the nose.
+    pub fn covers(&self, aim_rotation: f32) -> bool {
+        let mut difference = (aim_rotation - self.facing.to_radians()) % (PI * 2.);
+        if difference > PI {
+            difference -= PI * 2.;
+        } else if difference < -PI {
+            difference += PI * 2.;
+        }
+        difference.abs() <= self.arc.to_radians()
+    }
+}
+
+impl HullCatalogue {
+    pub fn from_ron(contents: &str) -> anyhow::Result<Self> {
+        let catalogue: Self = ron::from_str(contents)?;
+        for hull in catalogue.hulls.iter() {
+            if !Skeletons::NAMES.contains(&hull.skeleton.as_str()) {
+                bail!("{} has no skeleton called {:?}", hull.id, hull.skeleton);
+            }
+        }
+        Ok(catalogue)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&HullClass> {
+        self.hulls.iter().find(|hull| hull.id == id)
+    }
+
+    /// The hull the player is flying, falling back to the starting hull for unknown ids.
+    pub fn for_player(&self, player: &Player) -> &HullClass {
+        self.get(&player.hull_class)
+            .or_else(|| self.get(STARTING_HULL))
+            .unwrap_or(&self.hulls[0])
+    }
+}
+
+/// The hull the player's ship entity was last sized for.
+#[derive(Component, Debug)]
+struct FittedHull(String);
+
+// Hulls can change while docked, so the ship entity catches up with the player's hull.
+fn fit_player_hull(
+    mut commands: Commands,
+    hulls: Res<HullCatalogue>,
+    mut players: Query<(
+        Entity,
+        &Player,
+        &mut Transform,
+        &mut InertiaVolume,
+        &mut Spine,
+        Option<&FittedHull>,
+    )>,
+) {
+    for (entity, player, mut transform, mut inertia, mut spine, fitted) in players.iter_mut() {
+        if fitted.map_or(false, |fitted| fitted.0 == player.hull_class) {
+            continue;
+        }
+        let hull = hulls.for_player(player);
+        transform.scale = Vec3::splat(hull.scale);
+        inertia.mass = hull.mass;
+        inertia.radius = hull.radius;
+        // Bring back any bones the last hull hid.
+        spine.skeleton.set_bones_to_setup_pose();
+        commands
+            .entity(entity)
+            .insert(FittedHull(player.hull_class.clone()));
+    }
+}
+
+fn hide_unused_player_bones(hulls: Res<HullCatalogue>, mut players: Query<(&Player, &mut Spine)>) {
+    for (player, mut spine) in players.iter_mut() {
+        for bone in hulls.for_player(player).hidden_bones.iter() {
+            if let Some(mut hidden_bone) = spine.skeleton.find_bone_mut(bone) {
+                hidden_bone.set_scale_x(0.);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod hulls_tests {
+    use super::*;
+
+    #[test]
+    fn catalogue_parses() {
+        let catalogue = HullCatalogue::from_ron(include_str!("../assets/hulls.ron")).unwrap();
+        for hull in catalogue.hulls.iter() {
+            assert!(!hull.turrets.is_empty(), "{}", hull.id);
+            assert!(hull.price > 0.0, "{}", hull.id);
+        }
+        let raider = catalogue.get(STARTING_HULL).unwrap();
+        let player = Player::new();
+        for (stat, value) in raider.stats.iter() {
+            assert_eq!(player.stat(*stat), *value, "{:?}", stat);
+        }
+    }
+
+    #[test]
+    fn unknown_skeletons_are_rejected() {
+        let contents =
+            include_str!("../assets/hulls.ron").replace("\"player_ship\"", "\"player_shp\"");
+        let err = HullCatalogue::from_ron(&contents).err().unwrap();
+        assert!(err.to_string().contains("player_shp"));
+    }
+
+    #[test]
+    fn turrets_fire_within_their_arc() {
+        let left = HullTurret {
+            bone: "left_turret".to_string(),
+            facing: 90.0,
+            arc: 112.5,
+        };
+        assert!(left.covers(PI / 2.));
+        assert!(left.covers(PI));
+        assert!(left.covers(-PI * 15. / 16.));
+        assert!(!left.covers(-PI / 2.));
+        assert!(!left.covers(-PI / 4.));
+    }
+}
@@ -23,6 +23,7 @@ fn update_intro_text(
     players: Query<(&Player, &Transform)>,
     pickups: Query<&Pickup>,
     jammers: Query<&Jammer>,
+    mut rng: ResMut<GameRng>,
 ) {
     if career.intro_complete() || players.is_empty() {
         return;
@@ -67,7 +68,7 @@ fn update_intro_text(
                 let y = players.single().1.translation.y;
                 let x = x + 200.;
                 let y = y + 200.;
-                spawn_exotic(x, y, &mut commands, game_assets.exotic.clone(), 20.)
+                spawn_exotic(x, y, &mut commands, &mut rng.world, 20.)
                     .insert(DistantIndicator::new_local(indicator, indicator_text));
                 format!("Fly into the nearby XM asteroid to pick up some exotic matter.")
             }
@@ -119,7 +120,7 @@ fn update_intro_text(
                 if keys.just_pressed(KeyCode::Space) {
                     career.intro_stage += 1;
                 }
-                format!("Visit your hideout to end the day.\nThere, you'll buy repairs and install all upgrades.\n\nPress [Space] to continue.")
+                format!("Visit your hideout to end the day.\nThere, you'll sell your haul, buy repairs and fit upgrades.\n\nPress [Space] to continue.")
             }
             14 => {
                 if keys.just_pressed(KeyCode::Space) {
@@ -129,7 +130,7 @@ fn update_intro_text(
             }
             _ => {
                 career.intro_stage = INTRO_STAGES + 10;
-                spawn_starting_system(commands, game_assets);
+                spawn_starting_system(commands, game_assets, rng);
                 format!("")
             }
         }
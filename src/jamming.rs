@@ -1,6 +1,10 @@
-use bevy::sprite::MaterialMesh2dBundle;
+use bevy::{sprite::MaterialMesh2dBundle, utils::HashSet};
 
-use crate::prelude::*;
+use crate::{
+    effects::SoundEffect,
+    physics::{PhysicsSet, SpacialQuery},
+    prelude::*,
+};
 
 pub struct JammingPlugin;
 
@@ -10,10 +14,15 @@ impl Plugin for JammingPlugin {
             Update,
             (
                 deploy_jammer_system.run_if(in_state(GameState::Playing)),
-                generate_jamming_pixels.run_if(in_state(GameState::Playing)),
-                insert_jammed_around_jammer_system,
+                generate_jamming_pixels
+                    .run_if(in_state(GameState::Playing).and_then(resource_exists::<Lasers>())),
                 indicate_jamming_on_skeleton,
+                dress_jammers.run_if(resource_exists::<GameAssets>()),
             ),
+        )
+        .add_systems(
+            FixedUpdate,
+            insert_jammed_around_jammer_system.in_set(PhysicsSet::Resolve),
         );
     }
 }
@@ -30,28 +39,71 @@ pub struct Jammer {
     pub progress: f32,
 }
 
+/// Spawns a deployed jammer drifting along with `velocity`, without any visuals.
+pub fn spawn_jammer(
+    commands: &mut Commands,
+    translation: Vec3,
+    velocity: Vec2,
+    radius: f32,
+) -> Entity {
+    let mut inertia = InertiaVolume::new(1.0, 0.0);
+    inertia.velocity = velocity;
+    commands
+        .spawn((
+            SpatialBundle::from_transform(Transform::from_translation(translation)),
+            inertia,
+            Regional,
+            Jammer {
+                radius,
+                progress: 0.0,
+            },
+        ))
+        .id()
+}
+
+// XM asteroids are jammers too, but they are dressed as pickups.
+pub fn dress_jammers(
+    mut commands: Commands,
+    jammers: Query<Entity, (Added<Jammer>, Without<Pickup>)>,
+    game_assets: Res<GameAssets>,
+) {
+    for entity in jammers.iter() {
+        commands.add(InsertSafe {
+            entity,
+            bundle: (
+                Sprite {
+                    color: Color::rgba(10., 10., 0., 1.),
+                    ..Default::default()
+                },
+                game_assets.jammer.clone(),
+            ),
+        });
+    }
+}
+
 pub fn insert_jammed_around_jammer_system(
     mut commands: Commands,
-    mut queries: ParamSet<(
-        Query<(Entity, &Transform), With<Jammable>>,
-        Query<(&Transform, &Jammer)>,
-    )>,
+    jammables: Query<Entity, With<Jammable>>,
+    jammers: Query<(&Transform, &Jammer)>,
+    spacial_query: SpacialQuery,
 ) {
-    let jammables = queries
-        .p0()
-        .iter()
-        .map(|(entity, transform)| (entity, transform.translation))
-        .collect::<Vec<_>>();
-    for (jammed_entity, jammed_location) in &jammables {
-        if queries.p1().iter().any(|(transform, jammer)| {
-            transform.translation.distance(*jammed_location) < jammer.radius
-        }) {
+    let mut jammed = HashSet::new();
+    for (transform, jammer) in jammers.iter() {
+        jammed.extend(
+            spacial_query
+                .within_radius(transform.translation.truncate(), jammer.radius)
+                .into_iter()
+                .filter(|entity| jammables.contains(*entity)),
+        );
+    }
+    for jammable in jammables.iter() {
+        if jammed.contains(&jammable) {
             commands.add(InsertSafe {
-                entity: *jammed_entity,
+                entity: jammable,
                 bundle: Jammed,
             });
         } else {
-            commands.entity(*jammed_entity).remove::<Jammed>();
+            commands.entity(jammable).remove::<Jammed>();
         }
     }
 }
@@ -102,8 +154,9 @@ pub fn generate_jamming_pixels(
     mut commands: Commands,
     mut jammers: Query<(&mut Jammer, &Transform)>,
     lasers: Res<Lasers>,
+    mut rng: ResMut<GameRng>,
 ) {
-    let mut rng = rand::thread_rng();
+    let rng = &mut rng.cosmetic;
     let dt = time.delta_seconds();
     for (mut jammer, transform) in jammers.iter_mut() {
         let center = transform.translation;
@@ -125,7 +178,7 @@ pub fn generate_jamming_pixels(
                     material: lasers.jammer_material.clone(),
                     ..Default::default()
                 },
-                SpacePixel::random_jammer(),
+                SpacePixel::random_jammer(rng),
             ));
         }
     }
@@ -137,7 +190,7 @@ pub fn deploy_jammer_system(
     mut commands: Commands,
     mut player: Query<(&mut Player, &Transform, &mut InertiaVolume)>,
     input: Res<Input<KeyCode>>,
-    game_assets: Res<GameAssets>,
+    mut sound_effects: EventWriter<SoundEffect>,
 ) {
     if player.is_empty() {
         return;
@@ -146,37 +199,16 @@ pub fn deploy_jammer_system(
     if input.just_pressed(KeyCode::G) && *cooldown <= 0.0 {
         let (mut player, player_transform, mut player_inertia) = player.single_mut();
         if player.exotic_material < player.jammer_cost {
-            commands.spawn(AudioBundle {
-                source: game_assets.fail.clone(),
-                settings: PlaybackSettings::DESPAWN,
-            });
+            sound_effects.send(SoundEffect::Fail);
             return;
         }
-        commands.spawn(AudioBundle {
-            source: game_assets.deploy_jammer.clone(),
-            settings: PlaybackSettings::DESPAWN,
-        });
+        sound_effects.send(SoundEffect::DeployJammer);
         player.exotic_material -= player.jammer_cost;
-        let mut transform = Transform::default();
-        transform.translation = player_transform.translation;
-        let mut inertia = InertiaVolume::new(1.0, 0.0);
-        inertia.velocity = player_inertia.velocity;
-        commands.spawn((
-            SpriteBundle {
-                texture: game_assets.jammer.clone(),
-                transform,
-                sprite: Sprite {
-                    color: Color::rgba(10., 10., 0., 1.),
-                    ..Default::default()
-                },
-                ..Default::default()
-            },
-            inertia,
-            Regional,
-            Jammer {
-                radius: 1000.0 * player.jammer_range_multiplier,
-                progress: 0.0,
-            },
-        ));
+        spawn_jammer(
+            &mut commands,
+            player_transform.translation,
+            player_inertia.velocity,
+            1000.0 * player.jammer_range_multiplier,
+        );
     }
 }
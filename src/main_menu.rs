@@ -0,0 +1,93 @@
+use crate::{
+    heat::Heat,
+    home::Career,
+    prelude::*,
+    save::{read_save_file, restore_career, SAVE_PATH},
+    ui::UiState,
+};
+
+pub struct MainMenuPlugin;
+
+impl Plugin for MainMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MainMenu>()
+            .add_systems(OnEnter(GameState::MainMenu), spawn_menu_camera)
+            .add_systems(OnExit(GameState::MainMenu), despawn_menu_camera)
+            .add_systems(
+                Update,
+                (
+                    update_main_menu_text.run_if(in_state(GameState::MainMenu)),
+                    handle_main_menu_input.run_if(in_state(GameState::MainMenu)),
+                ),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct MainMenu {
+    // Why the last attempt to continue a career failed.
+    pub load_error: Option<String>,
+}
+
+#[derive(Component)]
+struct MenuCamera;
+
+fn spawn_menu_camera(mut commands: Commands) {
+    commands.spawn((Camera2dBundle::default(), MenuCamera));
+}
+
+fn despawn_menu_camera(mut commands: Commands, cameras: Query<Entity, With<MenuCamera>>) {
+    for camera in cameras.iter() {
+        commands.entity(camera).despawn_recursive();
+    }
+}
+
+fn update_main_menu_text(
+    ui_state: Res<UiState>,
+    main_menu: Res<MainMenu>,
+    mut texts: Query<&mut Text>,
+) {
+    if let Ok(mut text) = texts.get_mut(ui_state.central_text) {
+        text.sections[0].value = format!(
+            "Space Piracy 2444\n\n[N] Start a new career\n[C] Continue your saved career\n\n{}",
+            main_menu.load_error.as_deref().unwrap_or("")
+        );
+    }
+}
+
+fn handle_main_menu_input(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    mut main_menu: ResMut<MainMenu>,
+    mut career: ResMut<Career>,
+    mut heat: ResMut<Heat>,
+    mut rng: ResMut<GameRng>,
+    mut next_state: ResMut<NextState<GameState>>,
+    game_assets: Res<GameAssets>,
+    skeletons: Res<Skeletons>,
+    hulls: Res<HullCatalogue>,
+) {
+    if keys.just_pressed(KeyCode::N) {
+        next_state.set(GameState::Playing);
+    } else if keys.just_pressed(KeyCode::C) {
+        match read_save_file(SAVE_PATH) {
+            Ok(save) => {
+                restore_career(
+                    save,
+                    &mut commands,
+                    &game_assets,
+                    &skeletons,
+                    &hulls,
+                    &mut career,
+                    &mut heat,
+                    &mut rng,
+                );
+                main_menu.load_error = None;
+                next_state.set(GameState::Playing);
+            }
+            Err(err) => {
+                main_menu.load_error = Some(format!("{:#}", err));
+            }
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+// How much of the gap back to the base price closes overnight.
+const MEAN_REVERSION: f32 = 0.3;
+// However the market moves, prices stay within these multiples of the base price.
+const MIN_PRICE: f32 = 0.25;
+const MAX_PRICE: f32 = 3.0;
+
+/// What the hideout's fence pays per unit of cargo space for each commodity, on one day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MarketPrices(pub HashMap<Commodity, f32>);
+
+impl MarketPrices {
+    /// Every commodity at its base price.
+    pub fn base() -> Self {
+        Self(
+            Commodity::ALL
+                .iter()
+                .map(|commodity| (*commodity, commodity.market().base_price))
+                .collect(),
+        )
+    }
+
+    pub fn price(&self, commodity: Commodity) -> f32 {
+        self.0
+            .get(&commodity)
+            .copied()
+            .unwrap_or_else(|| commodity.market().base_price)
+    }
+
+    /// Tomorrow's prices. Each drifts back towards its base price, plus a random shock
+    /// as large as the commodity is volatile.
+    pub fn next_day(&self, rng: &mut impl Rng) -> Self {
+        Self(
+            Commodity::ALL
+                .iter()
+                .map(|commodity| {
+                    let profile = commodity.market();
+                    let price = self.price(*commodity);
+                    let shock = rng.gen_range(-1.0..1.0) * profile.volatility * profile.base_price;
+                    let next = price + (profile.base_price - price) * MEAN_REVERSION + shock;
+                    (
+                        *commodity,
+                        next.clamp(
+                            profile.base_price * MIN_PRICE,
+                            profile.base_price * MAX_PRICE,
+                        ),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// What selling `mass` of `commodity` in one go would fetch. Every unit sold knocks
+    /// the price down by the commodity's saturation, so big lots fetch less per unit.
+    pub fn quote(&self, commodity: Commodity, mass: f32) -> f32 {
+        let saturation = commodity.market().saturation;
+        let price = self.price(commodity);
+        if saturation <= 0. {
+            price * mass
+        } else {
+            price * (1. - (-saturation * mass).exp()) / saturation
+        }
+    }
+
+    /// Sells `mass` of `commodity`, leaving the price where the sale pushed it.
+    pub fn sell(&mut self, commodity: Commodity, mass: f32) -> f32 {
+        let proceeds = self.quote(commodity, mass);
+        let saturation = commodity.market().saturation;
+        self.0.insert(
+            commodity,
+            self.price(commodity) * (-saturation * mass).exp(),
+        );
+        proceeds
+    }
+}
+
+#[cfg(test)]
+mod market_tests {
+    use super::*;
+
+    #[test]
+    fn flooding_the_market_lowers_prices() {
+        let mut prices = MarketPrices::base();
+        let small_lot = prices.quote(Commodity::Contraband, 10.0);
+        let large_lot = prices.quote(Commodity::Contraband, 50.0);
+        assert!(small_lot < prices.price(Commodity::Contraband) * 10.0);
+        assert!(large_lot < small_lot * 5.0);
+
+        assert_eq!(prices.sell(Commodity::Contraband, 10.0), small_lot);
+        assert!(prices.price(Commodity::Contraband) < Commodity::Contraband.market().base_price);
+        // Selling in two lots on the same day is no better than one big lot.
+        let rest = prices.sell(Commodity::Contraband, 40.0);
+        assert!((small_lot + rest - large_lot).abs() < 0.01);
+    }
+
+    #[test]
+    fn prices_drift_within_bounds() {
+        let mut rng = GameRng::new(2444);
+        let mut prices = MarketPrices::base();
+        let mut moved = false;
+        for _ in 0..100 {
+            prices = prices.next_day(&mut rng.market);
+            for commodity in Commodity::ALL {
+                let base = commodity.market().base_price;
+                let price = prices.price(commodity);
+                assert!(price >= base * MIN_PRICE && price <= base * MAX_PRICE);
+                moved |= price != base;
+            }
+        }
+        assert!(moved);
+    }
+}
@@ -0,0 +1,291 @@
+use crate::{
+    effects::SoundEffect,
+    fighters::{
+        attack, fighter_bundle, fighter_sprite, fly_towards, intercept, Fighter, ATTACK_RANGE,
+        FIGHTER_CRUISE_SPEED, FIGHTER_MASS, FIGHTER_MAX_SPEED, FIGHTER_RADIUS,
+    },
+    home::{Career, HomeInSystem},
+    physics::PhysicsSet,
+    prelude::*,
+    trade_routes::CurrentSystemRegion,
+};
+
+pub struct PatrolsPlugin;
+
+impl Plugin for PatrolsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Piracy>()
+            .add_systems(
+                FixedUpdate,
+                patrol_ai_system
+                    .run_if(in_state(GameState::Playing))
+                    .in_set(PhysicsSet::Forces),
+            )
+            .add_systems(
+                Update,
+                (
+                    track_piracy_system.run_if(in_state(GameState::Playing)),
+                    deploy_patrols_system
+                        .run_if(in_state(GameState::Playing).and_then(resource_exists::<Career>())),
+                    dress_patrols.run_if(resource_exists::<GameAssets>()),
+                ),
+            );
+    }
+}
+
+/// A crime the authorities will hear about, sent for the current system.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct Piracy {
+    pub severity: f32,
+}
+
+/// How much piracy the authorities remember in a system. Fades over time, and isn't saved.
+#[derive(Component, Debug, Default)]
+pub struct PiracyRecord(pub f32);
+
+/// An authority `Fighter`. Patrols aren't `Jammable`: their drives are shielded
+/// against the jamming fields pirates rely on.
+#[derive(Component, Debug)]
+pub struct Patrol {
+    pub state: PatrolState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PatrolState {
+    /// Sweeping the system for the player.
+    Hunt { waypoint: Vec2 },
+    /// Closing in on the player's predicted position.
+    Pursue,
+    /// In weapons range.
+    Attack,
+}
+
+const PATROL_HULL: f32 = 40.0;
+// Shooting down the authorities is the worst piracy of all.
+const PATROL_INFAMY: f32 = 4.0;
+const DETECTION_RANGE: f32 = 1200.0;
+// A little further than detection, so patrols don't flicker between hunting and pursuing.
+const LOSE_RANGE: f32 = 1800.0;
+const WAYPOINT_REACHED: f32 = 100.0;
+
+const PATROLS_PER_DAY: f32 = 0.35;
+// One cargo ship's worth of destroyed sections brings one more patrol.
+const PATROLS_PER_PIRACY: f32 = 1.0 / 8.0;
+const MAX_PATROLS: usize = 6;
+const PIRACY_HALF_LIFE: f32 = 120.0;
+const REINFORCEMENT_DELAY: f32 = 10.0;
+
+/// How many patrols a system gets this far into the career, given its piracy record.
+pub fn patrol_count(days_survived: u32, piracy: f32) -> usize {
+    ((days_survived as f32 * PATROLS_PER_DAY + piracy * PATROLS_PER_PIRACY).floor() as usize)
+        .min(MAX_PATROLS)
+}
+
+impl PatrolState {
+    /// Patrols that lose the player go hunting where they were `last_seen`.
+    pub fn next(self, player_distance: Option<f32>, last_seen: Vec2) -> Self {
+        match (self, player_distance) {
+            (_, Some(distance)) if distance <= ATTACK_RANGE => PatrolState::Attack,
+            (PatrolState::Hunt { .. }, Some(distance)) if distance <= DETECTION_RANGE => {
+                PatrolState::Pursue
+            }
+            (PatrolState::Hunt { .. }, _) => self,
+            (_, Some(distance)) if distance <= LOSE_RANGE => PatrolState::Pursue,
+            _ => PatrolState::Hunt {
+                waypoint: last_seen,
+            },
+        }
+    }
+}
+
+/// Spawns a patrol fighter without any visuals.
+pub fn spawn_patrol(
+    commands: &mut Commands,
+    transform: Transform,
+    inertia: InertiaVolume,
+) -> Entity {
+    commands
+        .spawn((
+            fighter_bundle(transform, inertia, Fighter::new(PATROL_HULL, PATROL_INFAMY)),
+            Patrol {
+                state: PatrolState::Hunt {
+                    waypoint: Vec2::ZERO,
+                },
+            },
+        ))
+        .id()
+}
+
+pub fn dress_patrols(
+    mut commands: Commands,
+    patrols: Query<Entity, Added<Patrol>>,
+    game_assets: Res<GameAssets>,
+) {
+    for entity in patrols.iter() {
+        commands.add(InsertSafe {
+            entity,
+            bundle: fighter_sprite(&game_assets, Color::rgba(2., 4., 10., 1.)),
+        });
+    }
+}
+
+fn patrol_ai_system(
+    fixed_time: Res<FixedTime>,
+    mut patrols: Query<
+        (&mut Patrol, &mut Fighter, &Transform, &mut InertiaVolume),
+        Without<Player>,
+    >,
+    players: Query<(&Transform, &InertiaVolume), With<Player>>,
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+    mut sound_effects: EventWriter<SoundEffect>,
+) {
+    let dt = fixed_time.period.as_secs_f32();
+    let player = players.get_single().ok();
+    for (mut patrol, mut fighter, transform, mut inertia) in patrols.iter_mut() {
+        let position = transform.translation.truncate();
+        let player_position = player.map(|(player, _)| player.translation.truncate());
+        patrol.state = patrol.state.next(
+            player_position.map(|player_position| player_position.distance(position)),
+            player_position.unwrap_or(position),
+        );
+        match (patrol.state, player) {
+            (PatrolState::Hunt { waypoint }, _) => {
+                if waypoint.distance(position) < WAYPOINT_REACHED {
+                    patrol.state = PatrolState::Hunt {
+                        waypoint: Vec2::new(
+                            rng.world.gen_range(-ARENA_SIZE..ARENA_SIZE),
+                            rng.world.gen_range(-ARENA_SIZE..ARENA_SIZE),
+                        ),
+                    };
+                }
+                fly_towards(&mut inertia, waypoint - position, FIGHTER_CRUISE_SPEED, dt);
+            }
+            (PatrolState::Pursue, Some(player)) => {
+                intercept(position, &mut inertia, player, dt);
+            }
+            (PatrolState::Attack, Some(player)) => {
+                attack(
+                    &mut fighter,
+                    position,
+                    &mut inertia,
+                    player,
+                    dt,
+                    &mut commands,
+                    &mut sound_effects,
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+fn track_piracy_system(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut piracy: EventReader<Piracy>,
+    current_system: Query<Entity, With<CurrentSystemRegion>>,
+    mut records: Query<&mut PiracyRecord>,
+) {
+    let fade = 0.5_f32.powf(time.delta_seconds() / PIRACY_HALF_LIFE);
+    for mut record in records.iter_mut() {
+        record.0 *= fade;
+    }
+    let severity = piracy.iter().map(|piracy| piracy.severity).sum::<f32>();
+    if severity == 0. {
+        return;
+    }
+    if let Ok(system) = current_system.get_single() {
+        if let Ok(mut record) = records.get_mut(system) {
+            record.0 += severity;
+        } else {
+            commands.entity(system).insert(PiracyRecord(severity));
+        }
+    }
+}
+
+/// Brings patrols into the current system until there are as many as it deserves. They
+/// arrive one at a time from the edge of the arena.
+fn deploy_patrols_system(
+    mut visit: Local<Option<Entity>>,
+    mut deployed: Local<usize>,
+    mut cooldown: Local<f32>,
+    time: Res<Time>,
+    career: Res<Career>,
+    current_system: Query<Entity, With<CurrentSystemRegion>>,
+    systems: Query<Option<&PiracyRecord>, Without<HomeInSystem>>,
+    mut commands: Commands,
+    mut rng: ResMut<GameRng>,
+) {
+    let Ok(system) = current_system.get_single() else {
+        return;
+    };
+    // Count the hideout as a visit too, so coming back out of it calls in fresh patrols.
+    if *visit != Some(system) {
+        *visit = Some(system);
+        *deployed = 0;
+        *cooldown = 0.;
+    }
+    let Ok(m_record) = systems.get(system) else {
+        return;
+    };
+    *cooldown -= time.delta_seconds();
+    let wanted = patrol_count(
+        career.days_survived,
+        m_record.map(|record| record.0).unwrap_or(0.),
+    );
+    if *deployed >= wanted || *cooldown > 0. {
+        return;
+    }
+    *deployed += 1;
+    *cooldown = REINFORCEMENT_DELAY;
+    let rng = &mut rng.world;
+    let angle = rng.gen_range(0.0..PI * 2.0);
+    let direction = Vec2::from_angle(angle);
+    let mut inertia = InertiaVolume::new(FIGHTER_MASS, FIGHTER_RADIUS);
+    inertia.set_rotation(angle + PI);
+    inertia.velocity = -direction * FIGHTER_MAX_SPEED;
+    spawn_patrol(
+        &mut commands,
+        Transform::from_translation((direction * ARENA_SIZE * 1.5).extend(0.)),
+        inertia,
+    );
+}
+
+#[cfg(test)]
+mod patrols_tests {
+    use super::*;
+
+    #[test]
+    fn density_rises_with_days_and_piracy() {
+        assert_eq!(patrol_count(0, 0.0), 0);
+        assert!(patrol_count(6, 0.0) > patrol_count(2, 0.0));
+        assert!(patrol_count(2, 16.0) > patrol_count(2, 0.0));
+        assert_eq!(patrol_count(100, 100.0), MAX_PATROLS);
+    }
+
+    #[test]
+    fn hunt_pursue_attack() {
+        let hunting = PatrolState::Hunt {
+            waypoint: Vec2::ZERO,
+        };
+        assert_eq!(hunting.next(None, Vec2::ONE), hunting);
+        assert_eq!(hunting.next(Some(1500.0), Vec2::ONE), hunting);
+        assert_eq!(hunting.next(Some(1000.0), Vec2::ONE), PatrolState::Pursue);
+        assert_eq!(
+            PatrolState::Pursue.next(Some(300.0), Vec2::ONE),
+            PatrolState::Attack
+        );
+        // Keeps chasing past the detection range, until the player gets away.
+        assert_eq!(
+            PatrolState::Attack.next(Some(1500.0), Vec2::ONE),
+            PatrolState::Pursue
+        );
+        assert_eq!(
+            PatrolState::Pursue.next(Some(2000.0), Vec2::ONE),
+            PatrolState::Hunt {
+                waypoint: Vec2::ONE
+            }
+        );
+    }
+}
@@ -1,24 +1,96 @@
-use bevy::transform::TransformSystem;
+use std::time::Duration;
+
+use bevy::{
+    ecs::{
+        query::{ReadOnlyWorldQuery, WorldQuery},
+        system::SystemParam,
+    },
+    transform::{
+        systems::{propagate_transforms, sync_simple_transforms},
+        TransformSystem,
+    },
+};
 
 use crate::prelude::*;
 
-pub struct PhysicsPlugin;
+pub struct PhysicsPlugin {
+    // Physics steps per second.
+    pub tick_rate: f64,
+}
+
+impl Default for PhysicsPlugin {
+    fn default() -> Self {
+        Self { tick_rate: 60.0 }
+    }
+}
 
 impl Plugin for PhysicsPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<Collision>()
+            .add_event::<Ram>()
             .init_resource::<SpacialGrid>()
-            .add_systems(
+            .insert_resource(FixedTime::new(Duration::from_secs_f64(
+                1.0 / self.tick_rate,
+            )))
+            .configure_sets(
+                FixedUpdate,
+                (
+                    PhysicsSet::Sync,
+                    PhysicsSet::Forces,
+                    PhysicsSet::Collisions,
+                    PhysicsSet::Step,
+                    PhysicsSet::Resolve,
+                )
+                    .chain(),
+            )
+            .configure_sets(
                 PostUpdate,
-                maintain_spacial_grid.after(TransformSystem::TransformPropagate),
+                PhysicsSet::Interpolate.before(TransformSystem::TransformPropagate),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    restore_physics_transforms,
+                    sync_simple_transforms,
+                    propagate_transforms,
+                    maintain_spacial_grid,
+                )
+                    .chain()
+                    .in_set(PhysicsSet::Sync),
             )
             .add_systems(
-                Update,
-                (generate_collisions, apply_velocity, apply_rotation),
+                FixedUpdate,
+                (generate_collisions, resolve_solid_collisions)
+                    .chain()
+                    .in_set(PhysicsSet::Collisions),
+            )
+            .add_systems(
+                FixedUpdate,
+                ((apply_velocity, apply_rotation), record_physics_transforms)
+                    .chain()
+                    .in_set(PhysicsSet::Step),
+            )
+            .add_systems(
+                PostUpdate,
+                interpolate_physics_transforms.in_set(PhysicsSet::Interpolate),
             );
     }
 }
 
+/// The stages of a physics tick in `FixedUpdate`, in order. Gameplay systems that
+/// change velocities belong in `Forces`, and ones that react to collisions in `Resolve`.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PhysicsSet {
+    // Undoes render interpolation and refreshes world positions and the spacial grid.
+    Sync,
+    Forces,
+    Collisions,
+    Step,
+    Resolve,
+    // Blends transforms between the last two ticks, in `PostUpdate`.
+    Interpolate,
+}
+
 #[derive(Component, Debug, Clone)]
 pub struct InertiaVolume {
     pub velocity: Vec2,
@@ -28,8 +100,6 @@ pub struct InertiaVolume {
     pub radius: f32,
 }
 
-const COLLISION_TICK_LENGTH: f32 = 1.0 / 1000.0;
-
 impl InertiaVolume {
     pub fn new(mass: f32, radius: f32) -> Self {
         Self {
@@ -130,29 +200,48 @@ impl InertiaVolume {
         }
     }
 
+    /// How far this volume moves before touching `other`, which is `other_relative` away.
     pub fn find_collision(
         &self,
         other: &InertiaVolume,
-        mut other_relative: Vec2,
+        other_relative: Vec2,
         dt: f32,
     ) -> Option<Vec2> {
-        let mut distance_squared = other_relative.length_squared();
+        self.time_of_impact(other, other_relative, dt)
+            .map(|time| self.velocity * time)
+    }
+
+    /// Seconds into the next `dt` at which this volume first touches `other`.
+    pub fn time_of_impact(
+        &self,
+        other: &InertiaVolume,
+        other_relative: Vec2,
+        dt: f32,
+    ) -> Option<f32> {
+        // Solve |other_relative + relative_velocity * t| = radius_sum for the first t.
         let radius_sum = self.radius + other.radius;
-        let ticks = (dt / COLLISION_TICK_LENGTH) as usize + 1;
-        let tick_length = dt / ticks as f32;
-        let my_tick = self.velocity * tick_length;
-        let other_tick = other.velocity * tick_length;
-        let mut ticks_left = ticks;
-        while ticks_left > 0 && distance_squared > radius_sum * radius_sum {
-            other_relative -= my_tick;
-            other_relative += other_tick;
-            distance_squared = other_relative.length_squared();
-            ticks_left -= 1;
+        let relative_velocity = other.velocity - self.velocity;
+        let c = other_relative.length_squared() - radius_sum * radius_sum;
+        if c <= 0.0 {
+            // Already touching.
+            return Some(0.0);
+        }
+        let a = relative_velocity.length_squared();
+        let b = 2.0 * relative_velocity.dot(other_relative);
+        if a == 0.0 || b >= 0.0 {
+            // Not moving closer.
+            return None;
         }
-        if distance_squared > radius_sum * radius_sum {
+        let d = b * b - 4.0 * a * c;
+        if d < 0.0 {
             return None;
         }
-        Some((ticks - ticks_left) as f32 * my_tick)
+        let t = (-b - d.sqrt()) / (2.0 * a);
+        if t > dt {
+            None
+        } else {
+            Some(t)
+        }
     }
 }
 
@@ -249,6 +338,125 @@ impl SpacialGrid {
         SpaceIterator::new(self, self.cell(position))
     }
 
+    /// Every entity in a cell overlapping the box from `min` to `max`.
+    pub fn query_cells<'a>(&'a self, min: Vec2, max: Vec2) -> impl Iterator<Item = Entity> + 'a {
+        let (min_x, min_y) = self.cell(min);
+        let (max_x, max_y) = self.cell(max);
+        (min_x..=max_x).flat_map(move |x| {
+            (min_y..=max_y).flat_map(move |y| self.grid.get(&(x, y)).into_iter().flatten().copied())
+        })
+    }
+
+    pub fn within_radius(
+        &self,
+        center: Vec2,
+        radius: f32,
+        position: impl Fn(Entity) -> Option<Vec2>,
+    ) -> Vec<Entity> {
+        let reach = Vec2::splat(radius);
+        self.query_cells(center - reach, center + reach)
+            .filter(|entity| {
+                position(*entity)
+                    .map(|position| position.distance_squared(center) <= radius * radius)
+                    .unwrap_or(false)
+            })
+            .collect()
+    }
+
+    pub fn in_aabb(
+        &self,
+        min: Vec2,
+        max: Vec2,
+        position: impl Fn(Entity) -> Option<Vec2>,
+    ) -> Vec<Entity> {
+        self.query_cells(min, max)
+            .filter(|entity| {
+                position(*entity)
+                    .map(|position| position.cmpge(min).all() && position.cmple(max).all())
+                    .unwrap_or(false)
+            })
+            .collect()
+    }
+
+    /// Up to `count` entities within `max_distance`, closest first.
+    pub fn nearest(
+        &self,
+        center: Vec2,
+        count: usize,
+        max_distance: f32,
+        position: impl Fn(Entity) -> Option<Vec2>,
+    ) -> Vec<(Entity, f32)> {
+        let mut found = Vec::new();
+        if count == 0 {
+            return found;
+        }
+        let (center_x, center_y) = self.cell(center);
+        // Past the furthest occupied cell there's nothing left to find, however far we may look.
+        let occupied = self
+            .grid
+            .keys()
+            .map(|(x, y)| (x - center_x).abs().max((y - center_y).abs()))
+            .max()
+            .unwrap_or(0);
+        let reach = (max_distance / self.cell_size).ceil() as i32;
+        let last_ring = reach.saturating_add(1).min(occupied);
+        for ring in 0..=last_ring {
+            for x in (center_x - ring)..=(center_x + ring) {
+                for y in (center_y - ring)..=(center_y + ring) {
+                    if (x - center_x).abs() != ring && (y - center_y).abs() != ring {
+                        // Inside the ring, already searched.
+                        continue;
+                    }
+                    for entity in self.grid.get(&(x, y)).into_iter().flatten() {
+                        if let Some(distance) = position(*entity).map(|p| p.distance(center)) {
+                            if distance <= max_distance {
+                                found.push((*entity, distance));
+                            }
+                        }
+                    }
+                }
+            }
+            found.sort_by(|a, b| a.1.total_cmp(&b.1));
+            // Anything in a further ring is at least this far away.
+            if found.len() >= count && found[count - 1].1 <= ring as f32 * self.cell_size {
+                break;
+            }
+        }
+        found.truncate(count);
+        found
+    }
+
+    /// The first volume hit travelling from `start` to `end`, with how far along it was hit (0 to 1).
+    /// Volumes are found by their cell, so radii larger than a cell may be missed.
+    pub fn raycast(
+        &self,
+        start: Vec2,
+        end: Vec2,
+        volume: impl Fn(Entity) -> Option<(Vec2, f32)>,
+    ) -> Option<(Entity, f32)> {
+        let padding = Vec2::splat(self.cell_size);
+        let direction = end - start;
+        let a = direction.length_squared();
+        self.query_cells(start.min(end) - padding, start.max(end) + padding)
+            .filter_map(|entity| {
+                let (position, radius) = volume(entity)?;
+                let from_center = start - position;
+                let c = from_center.length_squared() - radius * radius;
+                if c <= 0.0 {
+                    // Starts inside it.
+                    return Some((entity, 0.0));
+                }
+                let b = 2.0 * from_center.dot(direction);
+                let d = b * b - 4.0 * a * c;
+                if a == 0.0 || d < 0.0 {
+                    return None;
+                }
+                let t = (-b - d.sqrt()) / (2.0 * a);
+                (0.0..=1.0).contains(&t).then_some((entity, t))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
     fn cell(&self, position: Vec2) -> (i32, i32) {
         (
             (position.x / self.cell_size).floor() as i32,
@@ -266,11 +474,215 @@ impl Default for SpacialGrid {
 #[derive(Component, Debug)]
 pub struct SpacialReference(Vec2);
 
+/// Broadphase lookups for gameplay systems. Positions are as of the start of the
+/// current physics tick.
+#[derive(SystemParam)]
+pub struct SpacialQuery<'w, 's> {
+    grid: Res<'w, SpacialGrid>,
+    volumes: Query<'w, 's, (&'static SpacialReference, &'static InertiaVolume)>,
+}
+
+impl<'w, 's> SpacialQuery<'w, 's> {
+    fn position(&self, entity: Entity) -> Option<Vec2> {
+        self.volumes
+            .get(entity)
+            .ok()
+            .map(|(reference, _)| reference.0)
+    }
+
+    pub fn within_radius(&self, center: Vec2, radius: f32) -> Vec<Entity> {
+        self.grid
+            .within_radius(center, radius, |entity| self.position(entity))
+    }
+
+    pub fn in_aabb(&self, min: Vec2, max: Vec2) -> Vec<Entity> {
+        self.grid.in_aabb(min, max, |entity| self.position(entity))
+    }
+
+    pub fn nearest(&self, center: Vec2, count: usize, max_distance: f32) -> Vec<Entity> {
+        self.grid
+            .nearest(center, count, max_distance, |entity| self.position(entity))
+            .into_iter()
+            .map(|(entity, _)| entity)
+            .collect()
+    }
+
+    /// The first volume accepted by `filter` between `start` and `end`, and where it was hit.
+    pub fn raycast(
+        &self,
+        start: Vec2,
+        end: Vec2,
+        filter: impl Fn(Entity) -> bool,
+    ) -> Option<(Entity, Vec2)> {
+        self.grid
+            .raycast(start, end, |entity| {
+                if !filter(entity) {
+                    return None;
+                }
+                self.volumes
+                    .get(entity)
+                    .ok()
+                    .map(|(reference, volume)| (reference.0, volume.radius))
+            })
+            .map(|(entity, t)| (entity, start.lerp(end, t)))
+    }
+}
+
+/// Two volumes touching during a physics tick. Each pair is sent once per tick, in no particular order.
 #[derive(Event, Debug, Clone)]
 pub struct Collision {
     pub e0: Entity,
     pub e1: Entity,
+    // The contact point, on the surface of `e0`.
     pub location: Vec2,
+    // Points from `e0` towards `e1`.
+    pub normal: Vec2,
+    // How far the volumes overlap at the time of impact, 0 if they only just touch.
+    pub depth: f32,
+    // When they touch, as a fraction of the tick.
+    pub time_of_impact: f32,
+}
+
+impl Collision {
+    /// Orders the pair as (entity in `first`, entity in `second`), if it matches them at all.
+    pub fn matching<Q0, F0, Q1, F1>(
+        &self,
+        first: &Query<Q0, F0>,
+        second: &Query<Q1, F1>,
+    ) -> Option<(Entity, Entity)>
+    where
+        Q0: WorldQuery,
+        F0: ReadOnlyWorldQuery,
+        Q1: WorldQuery,
+        F1: ReadOnlyWorldQuery,
+    {
+        if first.contains(self.e0) && second.contains(self.e1) {
+            Some((self.e0, self.e1))
+        } else if first.contains(self.e1) && second.contains(self.e0) {
+            Some((self.e1, self.e0))
+        } else {
+            None
+        }
+    }
+}
+
+/// Which kinds of volume an entity is, and which kinds it collides with. Volumes
+/// without layers never collide, and a pair only collides if each accepts the other.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollisionLayers {
+    pub memberships: u32,
+    pub filters: u32,
+}
+
+impl CollisionLayers {
+    pub const PLAYER: u32 = 1 << 0;
+    pub const CARGO_SHIP: u32 = 1 << 1;
+    pub const PLAYER_BULLET: u32 = 1 << 2;
+    pub const ENEMY_BULLET: u32 = 1 << 3;
+    pub const PICKUP: u32 = 1 << 4;
+    pub const FIGHTER: u32 = 1 << 5;
+
+    pub const fn new(memberships: u32, filters: u32) -> Self {
+        Self {
+            memberships,
+            filters,
+        }
+    }
+
+    pub fn interacts_with(&self, other: &CollisionLayers) -> bool {
+        self.memberships & other.filters != 0 && other.memberships & self.filters != 0
+    }
+}
+
+/// Marks a volume that bounces off other solid volumes instead of passing through them.
+/// A solid child (like a cargo section) pushes its parent's `InertiaVolume` around.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Solid {
+    // 0 for a dead stop, 1 for a perfectly elastic bounce.
+    pub restitution: f32,
+    // Whether hard impacts send `Ram` events for this volume.
+    pub takes_ram_damage: bool,
+}
+
+/// A solid volume was hit hard enough to hurt.
+#[derive(Event, Debug, Clone)]
+pub struct Ram {
+    pub entity: Entity,
+    // Kinetic energy lost along the contact normal.
+    pub energy: f32,
+}
+
+// Impacts gentler than this are just bumps.
+const MIN_RAM_ENERGY: f32 = 5_000.0;
+
+/// Where the physics step put an entity over the last two ticks. `Transform` is
+/// interpolated between them for rendering, and restored before the next tick.
+#[derive(Component, Debug, Clone)]
+pub struct PhysicsInterpolation {
+    previous: Transform,
+    current: Transform,
+    rendered: Transform,
+}
+
+impl PhysicsInterpolation {
+    fn new(transform: Transform) -> Self {
+        Self {
+            previous: transform,
+            current: transform,
+            rendered: transform,
+        }
+    }
+}
+
+fn restore_physics_transforms(
+    mut commands: Commands,
+    mut interpolated: Query<(&mut Transform, &mut PhysicsInterpolation)>,
+    new_volumes: Query<(Entity, &Transform), (With<InertiaVolume>, Without<PhysicsInterpolation>)>,
+) {
+    for (mut transform, mut interpolation) in interpolated.iter_mut() {
+        if transform.translation != interpolation.rendered.translation
+            || transform.rotation != interpolation.rendered.rotation
+        {
+            // Something outside of physics moved it, so it teleports there.
+            *interpolation = PhysicsInterpolation::new(*transform);
+        } else {
+            transform.translation = interpolation.current.translation;
+            transform.rotation = interpolation.current.rotation;
+        }
+    }
+    for (entity, transform) in new_volumes.iter() {
+        commands.add(InsertSafe {
+            entity,
+            bundle: PhysicsInterpolation::new(*transform),
+        });
+    }
+}
+
+fn record_physics_transforms(mut interpolated: Query<(&Transform, &mut PhysicsInterpolation)>) {
+    for (transform, mut interpolation) in interpolated.iter_mut() {
+        interpolation.previous = interpolation.current;
+        interpolation.current = *transform;
+        interpolation.rendered = *transform;
+    }
+}
+
+fn interpolate_physics_transforms(
+    fixed_time: Res<FixedTime>,
+    mut interpolated: Query<(&mut Transform, &mut PhysicsInterpolation)>,
+) {
+    let alpha =
+        (fixed_time.accumulated().as_secs_f32() / fixed_time.period.as_secs_f32()).clamp(0.0, 1.0);
+    for (mut transform, mut interpolation) in interpolated.iter_mut() {
+        transform.translation = interpolation
+            .previous
+            .translation
+            .lerp(interpolation.current.translation, alpha);
+        transform.rotation = interpolation
+            .previous
+            .rotation
+            .slerp(interpolation.current.rotation, alpha);
+        interpolation.rendered = *transform;
+    }
 }
 
 fn maintain_spacial_grid(
@@ -302,21 +714,31 @@ fn maintain_spacial_grid(
 }
 
 fn generate_collisions(
-    time: Res<Time>,
+    fixed_time: Res<FixedTime>,
     spacial_grid: Res<SpacialGrid>,
-    inertia_volumes: Query<(Entity, &GlobalTransform, &InertiaVolume, Option<&Parent>)>,
+    inertia_volumes: Query<(
+        Entity,
+        &GlobalTransform,
+        &InertiaVolume,
+        &CollisionLayers,
+        Option<&Parent>,
+    )>,
     mut collisions: EventWriter<Collision>,
 ) {
-    let dt = time.delta_seconds();
-    for (entity, transform, inertia_volume, m_parent) in inertia_volumes.iter() {
+    let dt = fixed_time.period.as_secs_f32();
+    for (entity, transform, inertia_volume, layers, m_parent) in inertia_volumes.iter() {
         let position = transform.translation().truncate();
         for other in spacial_grid.query(position) {
-            if other == entity || m_parent.map(|p| p.get() == other).unwrap_or(false) {
+            // Both sides of a pair find each other, so only the lower entity reports it.
+            if other <= entity || m_parent.map(|p| p.get() == other).unwrap_or(false) {
                 continue;
             }
-            if let Ok((_, other_transform, other_volume, other_parent)) = inertia_volumes.get(other)
+            if let Ok((_, other_transform, other_volume, other_layers, other_parent)) =
+                inertia_volumes.get(other)
             {
-                if other_parent.map(|p| p.get() == entity).unwrap_or(false) {
+                if !layers.interacts_with(other_layers) {
+                    continue;
+                } else if other_parent.map(|p| p.get() == entity).unwrap_or(false) {
                     continue;
                 } else if let (Some(my_parent), Some(other_parent)) = (m_parent, other_parent) {
                     if my_parent.get() == other_parent.get() {
@@ -324,12 +746,25 @@ fn generate_collisions(
                     }
                 }
                 let other_position = other_transform.translation().truncate();
-                let diff = position - other_position;
-                if let Some(collision) = inertia_volume.find_collision(other_volume, diff, dt) {
+                if let Some(time) =
+                    inertia_volume.time_of_impact(other_volume, other_position - position, dt)
+                {
+                    let my_impact_position = position + inertia_volume.velocity * time;
+                    let other_impact_position = other_position + other_volume.velocity * time;
+                    let delta = other_impact_position - my_impact_position;
+                    let distance = delta.length();
+                    let normal = if distance > 0.0 {
+                        delta / distance
+                    } else {
+                        Vec2::X
+                    };
                     collisions.send(Collision {
                         e0: entity,
                         e1: other,
-                        location: position + collision,
+                        location: my_impact_position + normal * inertia_volume.radius,
+                        normal,
+                        depth: (inertia_volume.radius + other_volume.radius - distance).max(0.0),
+                        time_of_impact: if dt > 0.0 { time / dt } else { 0.0 },
                     });
                 }
             }
@@ -337,18 +772,82 @@ fn generate_collisions(
     }
 }
 
-fn apply_velocity(time: Res<Time>, mut inertia_volumes: Query<(&mut Transform, &InertiaVolume)>) {
+fn resolve_solid_collisions(
+    mut collisions: EventReader<Collision>,
+    solids: Query<(&Solid, Option<&Parent>)>,
+    mut bodies: Query<(&mut Transform, &mut InertiaVolume)>,
+    mut rams: EventWriter<Ram>,
+) {
+    for collision in collisions.iter() {
+        let (Ok((solid_a, parent_a)), Ok((solid_b, parent_b))) =
+            (solids.get(collision.e0), solids.get(collision.e1))
+        else {
+            continue;
+        };
+        // Children move with their parent, so the parent takes the response.
+        let body_a = parent_a
+            .map(|parent| parent.get())
+            .filter(|parent| bodies.contains(*parent))
+            .unwrap_or(collision.e0);
+        let body_b = parent_b
+            .map(|parent| parent.get())
+            .filter(|parent| bodies.contains(*parent))
+            .unwrap_or(collision.e1);
+        if body_a == body_b {
+            continue;
+        }
+        let normal = collision.normal;
+        let Ok([(mut body_transform_a, mut body_a), (mut body_transform_b, mut body_b)]) =
+            bodies.get_many_mut([body_a, body_b])
+        else {
+            continue;
+        };
+        let inverse_mass_a = 1.0 / body_a.mass;
+        let inverse_mass_b = 1.0 / body_b.mass;
+        let inverse_mass_sum = inverse_mass_a + inverse_mass_b;
+        // Push them apart, the lighter one further.
+        if collision.depth > 0.0 {
+            let correction = normal * collision.depth / inverse_mass_sum;
+            body_transform_a.translation -= (correction * inverse_mass_a).extend(0.0);
+            body_transform_b.translation += (correction * inverse_mass_b).extend(0.0);
+        }
+        let approach_speed = (body_a.velocity - body_b.velocity).dot(normal);
+        if approach_speed <= 0.0 {
+            // Already separating.
+            continue;
+        }
+        let restitution = solid_a.restitution.min(solid_b.restitution);
+        let impulse = (1.0 + restitution) * approach_speed / inverse_mass_sum;
+        body_a.apply_impulse(-normal * impulse);
+        body_b.apply_impulse(normal * impulse);
+        let energy = 0.5 * approach_speed * approach_speed / inverse_mass_sum;
+        if energy >= MIN_RAM_ENERGY {
+            for (entity, solid) in [(collision.e0, solid_a), (collision.e1, solid_b)] {
+                if solid.takes_ram_damage {
+                    rams.send(Ram { entity, energy });
+                }
+            }
+        }
+    }
+}
+
+fn apply_velocity(
+    fixed_time: Res<FixedTime>,
+    mut inertia_volumes: Query<(&mut Transform, &InertiaVolume)>,
+) {
+    let dt = fixed_time.period.as_secs_f32();
     for (mut transform, inertia_volume) in inertia_volumes.iter_mut() {
-        transform.translation += inertia_volume.velocity.extend(0.0) * time.delta_seconds();
+        transform.translation += inertia_volume.velocity.extend(0.0) * dt;
     }
 }
 
 fn apply_rotation(
-    time: Res<Time>,
+    fixed_time: Res<FixedTime>,
     mut inertia_volumes: Query<(&mut Transform, &mut InertiaVolume)>,
 ) {
+    let dt = fixed_time.period.as_secs_f32();
     for (mut transform, mut inertia_volume) in inertia_volumes.iter_mut() {
-        inertia_volume.rotation += inertia_volume.rotation_velocity * time.delta_seconds();
+        inertia_volume.rotation += inertia_volume.rotation_velocity * dt;
         transform.rotation = Quat::from_rotation_z(inertia_volume.rotation);
     }
 }
@@ -356,6 +855,175 @@ fn apply_rotation(
 #[cfg(test)]
 mod physics_tests {
     use super::*;
+    use crate::test_harness::Simulation;
+
+    fn grid_with(points: &[Vec2]) -> (SpacialGrid, HashMap<Entity, Vec2>) {
+        let mut grid = SpacialGrid::default();
+        let mut positions = HashMap::new();
+        for (idx, point) in points.iter().enumerate() {
+            let entity = Entity::from_raw(idx as u32);
+            grid.insert(entity, *point);
+            positions.insert(entity, *point);
+        }
+        (grid, positions)
+    }
+
+    #[test]
+    fn spacial_queries() {
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(150.0, 0.0),
+            Vec2::new(900.0, 0.0),
+            Vec2::new(-450.0, 450.0),
+        ];
+        let (grid, positions) = grid_with(&points);
+        let position = |entity: Entity| positions.get(&entity).copied();
+        let mut near = grid.within_radius(Vec2::ZERO, 200.0, position);
+        near.sort();
+        assert_eq!(near, vec![Entity::from_raw(0), Entity::from_raw(1)]);
+        let nearest = grid.nearest(Vec2::new(800.0, 0.0), 2, 2000.0, position);
+        assert_eq!(nearest[0].0, Entity::from_raw(2));
+        assert_eq!(nearest[1].0, Entity::from_raw(1));
+        let boxed = grid.in_aabb(Vec2::new(-500.0, 400.0), Vec2::new(-400.0, 500.0), position);
+        assert_eq!(boxed, vec![Entity::from_raw(3)]);
+        let hit = grid.raycast(Vec2::new(-100.0, 0.0), Vec2::new(1000.0, 0.0), |entity| {
+            position(entity).map(|position| (position, 10.0))
+        });
+        let (entity, t) = hit.unwrap();
+        assert_eq!(entity, Entity::from_raw(0));
+        assert!((t - 90.0 / 1100.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn nearest_without_a_limit() {
+        let points = [Vec2::new(0.0, 0.0), Vec2::new(5000.0, -5000.0)];
+        let (grid, positions) = grid_with(&points);
+        let position = |entity: Entity| positions.get(&entity).copied();
+        // Asking for more than there are stops at the furthest occupied cell.
+        let nearest = grid.nearest(Vec2::ZERO, 3, f32::INFINITY, position);
+        let entities: Vec<_> = nearest.into_iter().map(|(entity, _)| entity).collect();
+        assert_eq!(entities, vec![Entity::from_raw(0), Entity::from_raw(1)]);
+    }
+
+    #[test]
+    fn solids_bounce() {
+        let mut simulation = Simulation::new();
+        let solid = Solid {
+            restitution: 1.0,
+            takes_ram_damage: false,
+        };
+        let layers = CollisionLayers::new(CollisionLayers::CARGO_SHIP, CollisionLayers::CARGO_SHIP);
+        let mut inertia = InertiaVolume::new(1.0, 16.0);
+        inertia.velocity = Vec2::new(100.0, 0.0);
+        let left = simulation.spawn(|commands| {
+            commands
+                .spawn((SpatialBundle::default(), inertia.clone(), solid, layers))
+                .id()
+        });
+        inertia.velocity = Vec2::new(-100.0, 0.0);
+        let right = simulation.spawn(|commands| {
+            commands
+                .spawn((
+                    SpatialBundle::from_transform(Transform::from_xyz(40.0, 0.0, 0.0)),
+                    inertia,
+                    solid,
+                    layers,
+                ))
+                .id()
+        });
+        simulation.step(30);
+        let left = simulation.get::<InertiaVolume>(left).unwrap().velocity;
+        let right = simulation.get::<InertiaVolume>(right).unwrap().velocity;
+        assert!((left.x + 100.0).abs() < 0.01, "{:?}", left);
+        assert!((right.x - 100.0).abs() < 0.01, "{:?}", right);
+    }
+
+    #[test]
+    fn layers_filter_collisions() {
+        let mut simulation = Simulation::new();
+        let bullet =
+            CollisionLayers::new(CollisionLayers::PLAYER_BULLET, CollisionLayers::CARGO_SHIP);
+        for _ in 0..2 {
+            simulation.spawn(|commands| {
+                commands.spawn((
+                    SpatialBundle::default(),
+                    InertiaVolume::new(1.0, 1.0),
+                    bullet,
+                ));
+            });
+        }
+        simulation.step(2);
+        assert!(simulation
+            .app
+            .world
+            .resource::<Events<Collision>>()
+            .is_empty());
+        let section =
+            CollisionLayers::new(CollisionLayers::CARGO_SHIP, CollisionLayers::PLAYER_BULLET);
+        simulation.spawn(|commands| {
+            commands.spawn((
+                SpatialBundle::default(),
+                InertiaVolume::new(1.0, 1.0),
+                section,
+            ));
+        });
+        simulation.step(2);
+        assert!(!simulation
+            .app
+            .world
+            .resource::<Events<Collision>>()
+            .is_empty());
+    }
+
+    #[test]
+    fn pairs_collide_once() {
+        let mut simulation = Simulation::new();
+        let layers = CollisionLayers::new(CollisionLayers::CARGO_SHIP, CollisionLayers::CARGO_SHIP);
+        let left = simulation.spawn(|commands| {
+            commands
+                .spawn((
+                    SpatialBundle::default(),
+                    InertiaVolume::new(1.0, 10.0),
+                    layers,
+                ))
+                .id()
+        });
+        let right = simulation.spawn(|commands| {
+            commands
+                .spawn((
+                    SpatialBundle::from_transform(Transform::from_xyz(15.0, 0.0, 0.0)),
+                    InertiaVolume::new(1.0, 10.0),
+                    layers,
+                ))
+                .id()
+        });
+        simulation.step(1);
+        let events = simulation.app.world.resource::<Events<Collision>>();
+        let collisions = events.iter_current_update_events().collect::<Vec<_>>();
+        assert_eq!(collisions.len(), 1);
+        let collision = collisions[0];
+        let mut pair = [collision.e0, collision.e1];
+        pair.sort();
+        let mut expected = [left, right];
+        expected.sort();
+        assert_eq!(pair, expected);
+        let towards_right = if collision.e0 == left { 1.0 } else { -1.0 };
+        assert_eq!(collision.normal, Vec2::new(towards_right, 0.0));
+        assert!((collision.depth - 5.0).abs() < 0.001);
+        assert_eq!(collision.time_of_impact, 0.0);
+    }
+
+    #[test]
+    fn moves_once_per_tick() {
+        let mut simulation = Simulation::new();
+        let mut inertia = InertiaVolume::new(1.0, 1.0);
+        inertia.velocity = Vec2::new(60.0, 0.0);
+        let entity =
+            simulation.spawn(|commands| commands.spawn((SpatialBundle::default(), inertia)).id());
+        simulation.step(60);
+        let interpolation = simulation.get::<PhysicsInterpolation>(entity).unwrap();
+        assert!((interpolation.current.translation.x - 60.0).abs() < 0.01);
+    }
 
     #[test]
     fn find_collision_easy() {
@@ -374,7 +1042,41 @@ mod physics_tests {
         going_down.velocity = Vec2::new(0.0, 500.0);
         let diff = Vec2::new(10.0, -10.0);
         let collision = going_right.find_collision(&going_down, diff, 1.0 / 30.0);
-        assert_eq!(collision, Some(Vec2::new(0.01764706 * 500., 0.)));
+        // They touch at t = 0.02 - sqrt(2) / 500.
+        let expected = Vec2::new((0.02 - 2f32.sqrt() / 500.) * 500., 0.);
+        assert!(
+            collision.unwrap().distance(expected) < 0.001,
+            "{:?}",
+            collision
+        );
+    }
+
+    #[test]
+    fn find_collision_no_tunnelling() {
+        // A bullet crossing a thin target in a fraction of one tick.
+        let mut bullet = InertiaVolume::new(1.0, 0.5);
+        bullet.velocity = Vec2::new(5000.0, 0.0);
+        let target = InertiaVolume::new(1.0, 0.5);
+        let diff = Vec2::new(7.0, 0.0);
+        let collision = bullet.find_collision(&target, diff, 1.0 / 60.0);
+        assert!(collision.unwrap().distance(Vec2::new(6.0, 0.0)) < 0.001);
+        // Even when it would be far past the target by the end of the tick.
+        let diff = Vec2::new(70.0, 0.0);
+        let time = bullet.time_of_impact(&target, diff, 1.0 / 60.0).unwrap();
+        assert!((time - 69.0 / 5000.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn find_collision_out_of_reach() {
+        let mut bullet = InertiaVolume::new(1.0, 0.5);
+        bullet.velocity = Vec2::new(1000.0, 0.0);
+        let target = InertiaVolume::new(1.0, 0.5);
+        // Too far to reach this tick.
+        let diff = Vec2::new(100.0, 0.0);
+        assert_eq!(bullet.find_collision(&target, diff, 1.0 / 60.0), None);
+        // Already behind the bullet.
+        let diff = Vec2::new(-5.0, 0.0);
+        assert_eq!(bullet.find_collision(&target, diff, 1.0 / 60.0), None);
     }
 
     #[test]
@@ -1,178 +1,412 @@
-use std::fmt::{Display, Formatter};
+use std::ops::Range;
 
 use bevy::ecs::system::EntityCommands;
+use rand::distributions::{Distribution, WeightedIndex};
+use serde::{Deserialize, Serialize};
 
-use crate::prelude::*;
+use crate::{effects::SoundEffect, physics::PhysicsSet, prelude::*};
 
 pub struct PickupsPlugin;
 
 impl Plugin for PickupsPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
+            FixedUpdate,
+            player_pickup_system
+                .run_if(not(in_state(GameState::Loading)))
+                .in_set(PhysicsSet::Resolve),
+        )
+        .add_systems(
             Update,
-            (player_pickup_system.run_if(not(in_state(GameState::Loading))),),
+            dress_pickups.run_if(
+                resource_exists::<GameAssets>().and_then(resource_exists::<UpgradeCatalogue>()),
+            ),
         );
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-pub enum Upgrade {
-    EngineUpgrade,
-    ShieldRecharge,
-    ShieldStrength,
-    HullStrength,
-    FireSpeed,
-    // FirePower,
-    JammerRange,
-    JammerEfficiency,
+#[derive(Component, Clone)]
+pub enum Pickup {
+    ExoticMaterial(f32),
+    Salvage {
+        commodity: Commodity,
+        mass: f32,
+        value: f32,
+    },
+    Upgrade {
+        mass: f32,
+        upgrade: Upgrade,
+    },
+}
+
+/// Goods that can be salvaged and sold, each with its own market.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Commodity {
+    /// Wreckage, from anything that gets blown up.
+    Scrap,
+    Ore,
+    Electronics,
+    Medical,
+    Contraband,
+}
+
+/// How a commodity trades: its base price per unit of cargo space, how far that drifts
+/// from day to day, and how much of the price is lost for each unit sold in one go.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarketProfile {
+    pub base_price: f32,
+    pub volatility: f32,
+    pub saturation: f32,
 }
 
-impl Display for Upgrade {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl Commodity {
+    pub const ALL: [Commodity; 5] = [
+        Commodity::Scrap,
+        Commodity::Ore,
+        Commodity::Electronics,
+        Commodity::Medical,
+        Commodity::Contraband,
+    ];
+
+    pub fn name(&self) -> &'static str {
         match self {
-            Upgrade::EngineUpgrade => write!(f, "Engine Upgrade"),
-            Upgrade::ShieldRecharge => write!(f, "Shield Recharge"),
-            Upgrade::ShieldStrength => write!(f, "Shield Strength"),
-            Upgrade::HullStrength => write!(f, "Hull Strength"),
-            Upgrade::FireSpeed => write!(f, "Fire Speed"),
-            // Upgrade::FirePower => write!(f, "Fire Power"),
-            Upgrade::JammerRange => write!(f, "Jammer Range"),
-            Upgrade::JammerEfficiency => write!(f, "Jammer Efficiency"),
+            Commodity::Scrap => "Scrap",
+            Commodity::Ore => "Ore",
+            Commodity::Electronics => "Electronics",
+            Commodity::Medical => "Medical Supplies",
+            Commodity::Contraband => "Contraband",
         }
     }
-}
 
-impl Upgrade {
-    pub fn get_upgrade_material_name(&self) -> String {
+    pub fn market(&self) -> MarketProfile {
         match self {
-            Upgrade::EngineUpgrade => "XM Engine Coils",
-            Upgrade::ShieldRecharge => "XM Shield Generators",
-            Upgrade::ShieldStrength => "XM Shield Capacitors",
-            Upgrade::HullStrength => "XM Plates",
-            Upgrade::FireSpeed => "XM Plasma Injectors",
-            // Upgrade::FirePower => "Fire Power Material",
-            Upgrade::JammerRange => "XM Attenuators",
-            Upgrade::JammerEfficiency => "XM Amplifiers",
+            Commodity::Scrap => MarketProfile {
+                base_price: 8.0,
+                volatility: 0.05,
+                saturation: 0.002,
+            },
+            Commodity::Ore => MarketProfile {
+                base_price: 5.0,
+                volatility: 0.1,
+                saturation: 0.001,
+            },
+            Commodity::Electronics => MarketProfile {
+                base_price: 14.0,
+                volatility: 0.15,
+                saturation: 0.004,
+            },
+            Commodity::Medical => MarketProfile {
+                base_price: 20.0,
+                volatility: 0.2,
+                saturation: 0.006,
+            },
+            Commodity::Contraband => MarketProfile {
+                base_price: 35.0,
+                volatility: 0.35,
+                saturation: 0.01,
+            },
         }
-        .to_string()
     }
 
-    pub fn get_sprite_index(&self) -> usize {
+    /// How much cargo space one piece takes up. Ore is bulky, contraband is easy to hide.
+    fn piece_mass(&self) -> Range<f32> {
         match self {
-            Upgrade::EngineUpgrade => 0,
-            Upgrade::ShieldRecharge => 1,
-            Upgrade::ShieldStrength => 2,
-            Upgrade::HullStrength => 3,
-            Upgrade::FireSpeed => 4,
-            // Upgrade::FirePower => 5,
-            Upgrade::JammerRange => 5,
-            Upgrade::JammerEfficiency => 6,
+            Commodity::Scrap => 1.0..2.0,
+            Commodity::Ore => 3.0..4.5,
+            Commodity::Electronics => 1.0..2.0,
+            Commodity::Medical => 1.0..1.5,
+            Commodity::Contraband => 0.5..1.0,
         }
     }
 
-    pub fn random() -> Self {
-        match rand::thread_rng().gen_range(0..7) {
-            0 => Upgrade::EngineUpgrade,
-            1 => Upgrade::ShieldRecharge,
-            2 => Upgrade::ShieldStrength,
-            3 => Upgrade::HullStrength,
-            4 => Upgrade::FireSpeed,
-            // 5 => Upgrade::FirePower,
-            5 => Upgrade::JammerRange,
-            6 => Upgrade::JammerEfficiency,
-            _ => panic!("Invalid upgrade index!"),
+    pub fn tint(&self) -> Color {
+        match self {
+            Commodity::Scrap => Color::rgba(2., 2., 2., 1.),
+            Commodity::Ore => Color::rgba(3., 1.5, 0.5, 1.),
+            Commodity::Electronics => Color::rgba(0.5, 3., 3., 1.),
+            Commodity::Medical => Color::rgba(4., 4., 4., 1.),
+            Commodity::Contraband => Color::rgba(4., 0.5, 3., 1.),
         }
     }
+
+    /// A piece of salvage as `(mass, value)`, scaled by how rich its ship was.
+    pub fn roll_piece(&self, rng: &mut impl Rng, value_modifier: f32) -> (f32, f32) {
+        let mass = rng.gen_range(self.piece_mass());
+        let value = mass * self.market().base_price * rng.gen_range(0.8..1.2) * value_modifier;
+        (mass, value)
+    }
 }
 
-#[derive(Component)]
-pub enum Pickup {
-    ExoticMaterial(f32),
-    Salvage { mass: f32, value: f32 },
-    Upgrade { mass: f32, upgrade: Upgrade },
+/// What a cargo ship is hauling, which decides what its sections drop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Manifest {
+    Ore,
+    Electronics,
+    Medical,
+    Contraband,
+    Xm,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LootDrop {
+    Salvage(Commodity),
+    Upgrade,
+}
+
+/// Rolled once for each destroyed cargo section: `pieces` drops, weighted by `drops`.
+pub struct LootTable {
+    pub pieces: usize,
+    pub drops: &'static [(LootDrop, f32)],
+}
+
+impl LootTable {
+    pub fn roll(&self, rng: &mut impl Rng) -> Vec<LootDrop> {
+        let Ok(weights) = WeightedIndex::new(self.drops.iter().map(|(_, weight)| *weight)) else {
+            return Vec::new();
+        };
+        (0..self.pieces)
+            .map(|_| self.drops[weights.sample(rng)].0)
+            .collect()
+    }
+}
+
+const MANIFEST_WEIGHTS: [(Manifest, f32); 5] = [
+    (Manifest::Ore, 4.0),
+    (Manifest::Electronics, 3.0),
+    (Manifest::Medical, 2.0),
+    (Manifest::Contraband, 1.0),
+    (Manifest::Xm, 1.0),
+];
+
+impl Manifest {
+    pub fn random(rng: &mut impl Rng) -> Self {
+        let weights = WeightedIndex::new(MANIFEST_WEIGHTS.iter().map(|(_, weight)| *weight))
+            .expect("Manifest weights are valid");
+        MANIFEST_WEIGHTS[weights.sample(rng)].0
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Manifest::Ore => "Ore",
+            Manifest::Electronics => "Electronics",
+            Manifest::Medical => "Medical Supplies",
+            Manifest::Contraband => "Contraband",
+            Manifest::Xm => "XM",
+        }
+    }
+
+    pub fn loot_table(&self) -> LootTable {
+        match self {
+            Manifest::Ore => LootTable {
+                pieces: 8,
+                drops: &[
+                    (LootDrop::Salvage(Commodity::Ore), 6.0),
+                    (LootDrop::Salvage(Commodity::Scrap), 1.5),
+                    (LootDrop::Upgrade, 0.5),
+                ],
+            },
+            Manifest::Electronics => LootTable {
+                pieces: 10,
+                drops: &[
+                    (LootDrop::Salvage(Commodity::Electronics), 7.0),
+                    (LootDrop::Salvage(Commodity::Scrap), 2.0),
+                    (LootDrop::Upgrade, 1.0),
+                ],
+            },
+            Manifest::Medical => LootTable {
+                pieces: 10,
+                drops: &[
+                    (LootDrop::Salvage(Commodity::Medical), 7.0),
+                    (LootDrop::Salvage(Commodity::Scrap), 2.0),
+                    (LootDrop::Upgrade, 1.0),
+                ],
+            },
+            Manifest::Contraband => LootTable {
+                pieces: 6,
+                drops: &[
+                    (LootDrop::Salvage(Commodity::Contraband), 4.0),
+                    (LootDrop::Salvage(Commodity::Scrap), 1.5),
+                    (LootDrop::Upgrade, 0.5),
+                ],
+            },
+            Manifest::Xm => LootTable {
+                pieces: 6,
+                drops: &[
+                    (LootDrop::Upgrade, 2.0),
+                    (LootDrop::Salvage(Commodity::Scrap), 3.0),
+                    (LootDrop::Salvage(Commodity::Electronics), 1.0),
+                ],
+            },
+        }
+    }
+}
+
+const PICKUP_LAYERS: CollisionLayers =
+    CollisionLayers::new(CollisionLayers::PICKUP, CollisionLayers::PLAYER);
+
+/// Spawns any pickup, without visuals. Used directly when restoring a system.
+pub fn spawn_pickup(
+    commands: &mut Commands,
+    transform: Transform,
+    inertia: InertiaVolume,
+    pickup: Pickup,
+) -> Entity {
+    commands
+        .spawn((
+            SpatialBundle::from_transform(transform),
+            Regional,
+            inertia,
+            PICKUP_LAYERS,
+            pickup,
+        ))
+        .id()
 }
 
 pub fn spawn_exotic<'w, 's, 'a>(
     x: f32,
     y: f32,
-    mut commands: &'a mut Commands<'w, 's>,
-    texture: Handle<Image>,
+    commands: &'a mut Commands<'w, 's>,
+    rng: &mut impl Rng,
     value: f32,
 ) -> EntityCommands<'w, 's, 'a> {
-    commands.spawn((
-        SpriteBundle {
-            transform: Transform::from_xyz(x, y, 0.0),
-            texture,
-            sprite: Sprite {
-                color: Color::rgba(10., 10., 0., 1.),
-                ..Default::default()
-            },
-            ..Default::default()
-        },
+    let exotic = spawn_pickup(
+        commands,
+        Transform::from_xyz(x, y, 0.0),
         InertiaVolume::new(1.0, 16.0),
         Pickup::ExoticMaterial(value),
-        Regional,
-        Jammer {
-            radius: rand::thread_rng().gen_range((value * 100.)..(value * 150.)),
-            progress: 0.0,
-        },
-    ))
+    );
+    let mut exotic = commands.entity(exotic);
+    exotic.insert(Jammer {
+        radius: rng.gen_range((value * 100.)..(value * 150.)),
+        progress: 0.0,
+    });
+    exotic
 }
 
 pub fn spawn_salvage(
     x: f32,
     y: f32,
     velocity: Vec2,
-    mut commands: &mut Commands<'_, '_>,
-    texture: Handle<Image>,
+    commands: &mut Commands<'_, '_>,
+    commodity: Commodity,
     mass: f32,
     value: f32,
-) {
+) -> Entity {
     let mut inertia_volume = InertiaVolume::new(1.0, 8.0);
     inertia_volume.velocity = velocity;
     inertia_volume.rotation_velocity = 0.1;
-    commands.spawn((
-        SpriteBundle {
-            transform: Transform::from_xyz(x, y, 0.0),
-            texture,
-            sprite: Sprite {
-                color: Color::rgba(value / 10., value / 10., value / 10., value / 10.),
-                ..Default::default()
-            },
-            ..Default::default()
-        },
-        Regional,
+    spawn_pickup(
+        commands,
+        Transform::from_xyz(x, y, 0.0),
         inertia_volume,
-        Pickup::Salvage { mass, value },
-    ));
+        Pickup::Salvage {
+            commodity,
+            mass,
+            value,
+        },
+    )
+}
+
+/// Spawns one rolled drop from a loot table. Upgrades may not drop if the catalogue is empty.
+pub fn spawn_loot(
+    x: f32,
+    y: f32,
+    velocity: Vec2,
+    commands: &mut Commands<'_, '_>,
+    rng: &mut impl Rng,
+    drop: LootDrop,
+    value_modifier: f32,
+    upgrades: &UpgradeCatalogue,
+) -> Option<Entity> {
+    match drop {
+        LootDrop::Salvage(commodity) => {
+            let (mass, value) = commodity.roll_piece(rng, value_modifier);
+            Some(spawn_salvage(
+                x, y, velocity, commands, commodity, mass, value,
+            ))
+        }
+        LootDrop::Upgrade => upgrades
+            .random(rng)
+            .map(|upgrade| spawn_upgrade(x, y, velocity, commands, upgrade)),
+    }
 }
 
 pub fn spawn_upgrade(
     x: f32,
     y: f32,
     velocity: Vec2,
-    mut commands: &mut Commands<'_, '_>,
-    texture_atlas: Handle<TextureAtlas>,
+    commands: &mut Commands<'_, '_>,
     upgrade: Upgrade,
-) {
+) -> Entity {
     let mut inertia_volume = InertiaVolume::new(1.0, 8.0);
     inertia_volume.velocity = velocity;
     inertia_volume.rotation_velocity = 0.1;
-    commands.spawn((
-        SpriteSheetBundle {
-            transform: Transform::from_xyz(x, y, 0.0),
-            texture_atlas,
-            sprite: TextureAtlasSprite {
-                index: upgrade.get_sprite_index(),
-                color: Color::rgba(8., 8., 4., 1.),
-                ..Default::default()
-            },
-            ..Default::default()
-        },
-        Regional,
+    spawn_pickup(
+        commands,
+        Transform::from_xyz(x, y, 0.0),
         inertia_volume,
         Pickup::Upgrade { mass: 10., upgrade },
-    ));
+    )
+}
+
+/// Gives newly spawned pickups their sprites.
+pub fn dress_pickups(
+    mut commands: Commands,
+    pickups: Query<(Entity, &Pickup), Added<Pickup>>,
+    game_assets: Res<GameAssets>,
+    upgrades: Res<UpgradeCatalogue>,
+) {
+    for (entity, pickup) in pickups.iter() {
+        match pickup {
+            Pickup::ExoticMaterial(_) => {
+                commands.add(InsertSafe {
+                    entity,
+                    bundle: (
+                        Sprite {
+                            color: Color::rgba(10., 10., 0., 1.),
+                            ..Default::default()
+                        },
+                        game_assets.exotic.clone(),
+                    ),
+                });
+            }
+            Pickup::Salvage {
+                commodity, value, ..
+            } => {
+                // Brighter for more valuable pieces.
+                let brightness = (value / 20.).clamp(0.5, 2.0);
+                let tint = commodity.tint();
+                commands.add(InsertSafe {
+                    entity,
+                    bundle: (
+                        Sprite {
+                            color: Color::rgba(
+                                tint.r() * brightness,
+                                tint.g() * brightness,
+                                tint.b() * brightness,
+                                tint.a(),
+                            ),
+                            ..Default::default()
+                        },
+                        game_assets.salvage.clone(),
+                    ),
+                });
+            }
+            Pickup::Upgrade { upgrade, .. } => {
+                commands.add(InsertSafe {
+                    entity,
+                    bundle: (
+                        TextureAtlasSprite {
+                            index: upgrades.atlas_index(upgrade),
+                            color: Color::rgba(8., 8., 4., 1.),
+                            ..Default::default()
+                        },
+                        game_assets.upgrades.clone(),
+                    ),
+                });
+            }
+        }
+    }
 }
 
 fn player_pickup_system(
@@ -180,49 +414,64 @@ fn player_pickup_system(
     mut collisions: EventReader<Collision>,
     mut players: Query<&mut Player>,
     pickups: Query<&Pickup>,
-    game_assets: Res<GameAssets>,
+    mut sound_effects: EventWriter<SoundEffect>,
 ) {
     for collision in collisions.iter() {
-        if let Ok(mut player) = players.get_mut(collision.e0) {
-            if let Ok(pickup) = pickups.get(collision.e1) {
+        if let Some((player_entity, pickup_entity)) = collision.matching(&players, &pickups) {
+            if let (Ok(mut player), Ok(pickup)) =
+                (players.get_mut(player_entity), pickups.get(pickup_entity))
+            {
                 match pickup {
                     Pickup::ExoticMaterial(amount) => {
                         if *amount > player.cargo_space_left() {
                             continue;
                         }
-                        commands.spawn(AudioBundle {
-                            source: game_assets.pickup_xm.clone(),
-                            settings: PlaybackSettings::DESPAWN,
-                        });
+                        sound_effects.send(SoundEffect::PickupXm);
                         player.exotic_material += amount.min(player.cargo_space_left());
                     }
-                    Pickup::Salvage { mass, value } => {
+                    Pickup::Salvage {
+                        commodity,
+                        mass,
+                        value,
+                    } => {
                         if *mass > player.cargo_space_left() {
                             continue;
                         }
-                        commands.spawn(AudioBundle {
-                            source: game_assets.pickup.clone(),
-                            settings: PlaybackSettings::DESPAWN,
-                        });
-                        player.salvage_mass += mass;
-                        player.salvage_value += value;
+                        sound_effects.send(SoundEffect::Pickup);
+                        player.load_commodity(*commodity, *mass, *value);
                     }
                     Pickup::Upgrade { mass, upgrade } => {
                         if *mass > player.cargo_space_left() {
                             continue;
                         }
-                        commands.spawn(AudioBundle {
-                            source: game_assets.upgrade.clone(),
-                            settings: PlaybackSettings::DESPAWN,
-                        });
+                        sound_effects.send(SoundEffect::Upgrade);
                         player.upgrade_mass += mass;
-                        player.upgrade_materials.push(*upgrade);
+                        player.upgrade_materials.push(upgrade.clone());
                     }
                 }
-                if let Some(mut pickup_entity) = commands.get_entity(collision.e1) {
+                if let Some(mut pickup_entity) = commands.get_entity(pickup_entity) {
                     pickup_entity.despawn();
                 }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod pickups_tests {
+    use super::*;
+
+    #[test]
+    fn manifests_drop_their_own_goods() {
+        let mut rng = GameRng::new(2444);
+        let drops = Manifest::Medical.loot_table().roll(&mut rng.loot);
+        assert_eq!(drops.len(), 10);
+        assert!(drops.contains(&LootDrop::Salvage(Commodity::Medical)));
+        assert!(!drops.contains(&LootDrop::Salvage(Commodity::Ore)));
+        // Contraband is worth far more for the space it takes up.
+        let (ore_mass, ore_value) = Commodity::Ore.roll_piece(&mut rng.loot, 1.0);
+        let (contraband_mass, contraband_value) =
+            Commodity::Contraband.roll_piece(&mut rng.loot, 1.0);
+        assert!(contraband_value / contraband_mass > ore_value / ore_mass);
+    }
+}
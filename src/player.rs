@@ -5,8 +5,18 @@ use bevy::{
     sprite::MaterialMesh2dBundle,
     transform::TransformSystem,
 };
+use serde::{Deserialize, Serialize};
 
-use crate::{assets::Skeletons, prelude::*};
+use crate::{
+    assets::Skeletons,
+    damage::{Damage, DamageType, Resistances},
+    hulls::{HullCatalogue, HullClass, STARTING_HULL},
+    market::MarketPrices,
+    physics::PhysicsSet,
+    prelude::*,
+    secondary_weapons::SecondarySlot,
+    upgrades::{PlayerStat, StatModifier, UpgradeDefinition},
+};
 
 // Define a plugin for the player.
 pub struct PlayerPlugin;
@@ -16,7 +26,7 @@ impl Plugin for PlayerPlugin {
         // Add the player to the world.
         app.add_systems(
             OnTransition {
-                from: GameState::Loading,
+                from: GameState::MainMenu,
                 to: GameState::Playing,
             },
             spawn_player,
@@ -38,13 +48,19 @@ impl Plugin for PlayerPlugin {
         .add_systems(
             PostUpdate,
             player_camera_system
+                .after(PhysicsSet::Interpolate)
                 .before(TransformSystem::TransformPropagate)
                 .run_if(not(in_state(GameState::Hyperdrive))),
         )
+        .add_systems(
+            FixedUpdate,
+            player_movement_system
+                .run_if(in_state(GameState::Playing))
+                .in_set(PhysicsSet::Forces),
+        )
         .add_systems(
             Update,
             (
-                player_movement_system.run_if(in_state(GameState::Playing)),
                 player_laser_aim_system.run_if(in_state(GameState::Playing)),
                 player_laser_fire_system.run_if(in_state(GameState::Playing)),
                 player_shield_recharge_system,
@@ -56,8 +72,11 @@ impl Plugin for PlayerPlugin {
 }
 
 // Define a component for the player.
-#[derive(Component)]
+#[derive(Component, Clone, Serialize, Deserialize)]
 pub struct Player {
+    /// Which of the `HullCatalogue` hulls the ship is built on.
+    #[serde(default = "starting_hull")]
+    pub hull_class: String,
     // Values for animation, set by player controls.
     pub thrust: f32,
     pub side_braking: f32,
@@ -70,13 +89,23 @@ pub struct Player {
     // Cooldown for main weapon.
     pub main_cooldown: f32,
     pub main_speed: f32,
+    #[serde(default = "default_laser_damage")]
+    pub laser_damage: f32,
+    #[serde(default)]
+    pub laser_penetration: f32,
     // Hyperdrive
+    #[serde(skip)]
     pub hyperdrive_target: Option<Entity>,
     // Health.
     pub max_shields: f32,
     pub shields: f32,
     pub max_hull: f32,
     pub hull: f32,
+    /// None by default, but some hulls come with them.
+    #[serde(default)]
+    pub shield_resistances: Resistances,
+    #[serde(default)]
+    pub hull_resistances: Resistances,
     pub shield_recharge_delay: f32,
     pub shield_recharge_timer: f32,
     pub shield_recharge_rate: f32,
@@ -84,18 +113,41 @@ pub struct Player {
     // Cargo.
     pub max_cargo: f32,
     pub salvage_mass: f32,
-    pub salvage_value: f32,
+    /// Cargo space taken up by each kind of salvage.
+    #[serde(default)]
+    pub commodities: HashMap<Commodity, f32>,
+    /// What the salvage of each kind is worth at base prices. Richer ships drop pieces
+    /// worth more for their mass.
+    #[serde(default)]
+    pub commodity_values: HashMap<Commodity, f32>,
     pub exotic_material: f32,
     pub upgrade_mass: f32,
     pub upgrade_materials: Vec<Upgrade>,
     // Upgrades
     pub jammer_range_multiplier: f32,
     pub jammer_cost: f32,
+    /// Every upgrade fitted so far, to carry over to a new hull.
+    #[serde(default)]
+    pub installed_upgrades: Vec<Upgrade>,
+    // Secondary weapons.
+    #[serde(default)]
+    pub secondaries: Vec<SecondarySlot>,
+    #[serde(default)]
+    pub selected_secondary: usize,
+}
+
+fn starting_hull() -> String {
+    STARTING_HULL.to_string()
+}
+
+fn default_laser_damage() -> f32 {
+    5.0
 }
 
 impl Player {
     pub fn new() -> Self {
         Self {
+            hull_class: starting_hull(),
             thrust: 0.0,
             side_braking: 0.0,
             aim_rotation: 0.0,
@@ -104,71 +156,154 @@ impl Player {
             thrust_braking_strength: 400.0,
             main_cooldown: 0.0,
             main_speed: 1.0 / 2.0,
+            laser_damage: default_laser_damage(),
+            laser_penetration: 0.0,
             hyperdrive_target: None,
             max_shields: 100.0,
             shields: 100.0,
             max_hull: 100.0,
             hull: 100.0,
+            shield_resistances: Resistances::default(),
+            hull_resistances: Resistances::default(),
             shield_recharge_delay: 5.0,
             shield_recharge_timer: 0.0,
             shield_recharge_rate: 10.0,
             repair_cost_per_hull: 5.0,
             max_cargo: 100.0,
             salvage_mass: 0.0,
-            salvage_value: 0.0,
+            commodities: HashMap::default(),
+            commodity_values: HashMap::default(),
             exotic_material: 0.0,
             upgrade_mass: 0.0,
             upgrade_materials: Vec::new(),
             jammer_range_multiplier: 1.0,
             jammer_cost: 20.0,
+            installed_upgrades: Vec::new(),
+            secondaries: Vec::new(),
+            selected_secondary: 0,
         }
     }
 
-    pub fn apply_upgrade(&mut self, upgrade: Upgrade) {
-        match upgrade {
-            Upgrade::EngineUpgrade => {
-                self.speed_limit *= 1.1;
-                self.engine_strength *= 1.1;
-                self.thrust_braking_strength *= 1.1;
+    /// A fresh ship built on `hull`, fully repaired.
+    pub fn with_hull(hull: &HullClass) -> Self {
+        let mut player = Self::new();
+        player.hull_class = hull.id.clone();
+        for (stat, value) in hull.stats.iter() {
+            *player.stat_mut(*stat) = *value;
+        }
+        if let Some(resistances) = hull.shield_resistances {
+            player.shield_resistances = resistances;
+        }
+        if let Some(resistances) = hull.hull_resistances {
+            player.hull_resistances = resistances;
+        }
+        player.shields = player.max_shields;
+        player.hull = player.max_hull;
+        player
+    }
+
+    /// This ship's cargo and upgrades moved over to a new `hull`.
+    pub fn refit(&self, hull: &HullClass, upgrades: &UpgradeCatalogue) -> Self {
+        let mut player = Self::with_hull(hull);
+        for upgrade in self.installed_upgrades.iter() {
+            if let Some(definition) = upgrades.get(upgrade) {
+                player.apply_upgrade(definition);
             }
-            Upgrade::ShieldStrength => {
-                self.max_shields *= 1.1;
+        }
+        player.salvage_mass = self.salvage_mass;
+        player.commodities = self.commodities.clone();
+        player.commodity_values = self.commodity_values.clone();
+        player.exotic_material = self.exotic_material;
+        player.upgrade_mass = self.upgrade_mass;
+        player.upgrade_materials = self.upgrade_materials.clone();
+        // Weapons that don't fit the new hull are scrapped with the old one.
+        player.secondaries = self
+            .secondaries
+            .iter()
+            .take(hull.secondary_slots)
+            .cloned()
+            .collect();
+        player
+    }
+
+    pub fn apply_upgrade(&mut self, upgrade: &UpgradeDefinition) {
+        for modifier in upgrade.modifiers.iter() {
+            self.apply_modifier(modifier);
+        }
+        self.installed_upgrades.push(Upgrade(upgrade.id.clone()));
+    }
+
+    pub fn apply_modifier(&mut self, modifier: &StatModifier) {
+        let original_hull = self.max_hull;
+        let stat = self.stat_mut(modifier.stat());
+        *stat = modifier.apply(*stat);
+        match modifier.stat() {
+            PlayerStat::MaxShields => {
                 self.shields = self.max_shields;
             }
-            Upgrade::HullStrength => {
-                let original = self.max_hull;
-                self.max_hull *= 1.1;
-                self.hull += self.max_hull - original;
-            }
-            Upgrade::ShieldRecharge => {
-                self.shield_recharge_delay *= 0.9;
-                self.shield_recharge_rate *= 1.1;
-            }
-            Upgrade::FireSpeed => {
-                self.main_speed *= 0.9;
-            }
-            Upgrade::JammerRange => {
-                self.jammer_range_multiplier *= 1.2;
-            }
-            Upgrade::JammerEfficiency => {
-                if self.jammer_cost <= 3.0 {
-                    self.jammer_cost *= 0.9;
-                } else {
-                    self.jammer_cost -= 3.0;
-                }
+            PlayerStat::MaxHull => {
+                self.hull += self.max_hull - original_hull;
             }
+            _ => {}
         }
     }
 
-    pub fn take_damage(&mut self, amount: f32) {
+    pub fn stat(&self, stat: PlayerStat) -> f32 {
+        match stat {
+            PlayerStat::SpeedLimit => self.speed_limit,
+            PlayerStat::EngineStrength => self.engine_strength,
+            PlayerStat::ThrustBrakingStrength => self.thrust_braking_strength,
+            PlayerStat::MainSpeed => self.main_speed,
+            PlayerStat::LaserDamage => self.laser_damage,
+            PlayerStat::LaserPenetration => self.laser_penetration,
+            PlayerStat::MaxShields => self.max_shields,
+            PlayerStat::ShieldRechargeDelay => self.shield_recharge_delay,
+            PlayerStat::ShieldRechargeRate => self.shield_recharge_rate,
+            PlayerStat::MaxHull => self.max_hull,
+            PlayerStat::RepairCostPerHull => self.repair_cost_per_hull,
+            PlayerStat::MaxCargo => self.max_cargo,
+            PlayerStat::JammerRangeMultiplier => self.jammer_range_multiplier,
+            PlayerStat::JammerCost => self.jammer_cost,
+        }
+    }
+
+    fn stat_mut(&mut self, stat: PlayerStat) -> &mut f32 {
+        match stat {
+            PlayerStat::SpeedLimit => &mut self.speed_limit,
+            PlayerStat::EngineStrength => &mut self.engine_strength,
+            PlayerStat::ThrustBrakingStrength => &mut self.thrust_braking_strength,
+            PlayerStat::MainSpeed => &mut self.main_speed,
+            PlayerStat::LaserDamage => &mut self.laser_damage,
+            PlayerStat::LaserPenetration => &mut self.laser_penetration,
+            PlayerStat::MaxShields => &mut self.max_shields,
+            PlayerStat::ShieldRechargeDelay => &mut self.shield_recharge_delay,
+            PlayerStat::ShieldRechargeRate => &mut self.shield_recharge_rate,
+            PlayerStat::MaxHull => &mut self.max_hull,
+            PlayerStat::RepairCostPerHull => &mut self.repair_cost_per_hull,
+            PlayerStat::MaxCargo => &mut self.max_cargo,
+            PlayerStat::JammerRangeMultiplier => &mut self.jammer_range_multiplier,
+            PlayerStat::JammerCost => &mut self.jammer_cost,
+        }
+    }
+
+    pub fn take_damage(&mut self, damage: &Damage) {
         self.shield_recharge_timer = self.shield_recharge_delay;
         if self.shields > 0.0 {
-            self.shields -= amount;
+            self.shields -= damage.against(&self.shield_resistances);
             if self.shields < 0.0 {
                 self.shields = 0.0;
             }
         } else {
-            self.hull -= amount;
+            self.hull -= damage.against(&self.hull_resistances);
+        }
+    }
+
+    /// What each of the ship's laser bolts does.
+    pub fn laser_damage(&self) -> Damage {
+        Damage {
+            amount: self.laser_damage,
+            damage_type: DamageType::Energy,
+            penetration: self.laser_penetration,
         }
     }
 
@@ -184,6 +319,67 @@ impl Player {
         (self.max_hull - self.hull) * self.repair_cost_per_hull
     }
 
+    pub fn load_commodity(&mut self, commodity: Commodity, mass: f32, value: f32) {
+        self.salvage_mass += mass;
+        *self.commodities.entry(commodity).or_default() += mass;
+        *self.commodity_values.entry(commodity).or_default() += value;
+    }
+
+    /// Takes every piece of `commodity` out of the hold, returning its mass.
+    pub fn unload_commodity(&mut self, commodity: Commodity) -> f32 {
+        let mass = self.commodities.remove(&commodity).unwrap_or(0.);
+        self.commodity_values.remove(&commodity);
+        self.salvage_mass = (self.salvage_mass - mass).max(0.);
+        mass
+    }
+
+    /// How much the `commodity` in the hold is worth, relative to the same mass at its
+    /// base price.
+    pub fn commodity_quality(&self, commodity: Commodity) -> f32 {
+        let mass = self.commodities.get(&commodity).copied().unwrap_or(0.);
+        let value = self.commodity_values.get(&commodity).copied().unwrap_or(0.);
+        let base_value = mass * commodity.market().base_price;
+        if base_value > 0. && value > 0. {
+            value / base_value
+        } else {
+            1.
+        }
+    }
+
+    /// What the whole hold would fetch at `prices`, before the fence takes a cut.
+    pub fn cargo_value(&self, prices: &MarketPrices) -> f32 {
+        self.commodities
+            .iter()
+            .map(|(commodity, mass)| {
+                prices.quote(*commodity, *mass) * self.commodity_quality(*commodity)
+            })
+            .sum()
+    }
+
+    /// Takes one piece of `upgrade` material out of the hold, if there is one, returning
+    /// the cargo space it took up.
+    pub fn unload_upgrade_material(&mut self, upgrade: &Upgrade) -> Option<f32> {
+        let idx = self
+            .upgrade_materials
+            .iter()
+            .position(|held| held == upgrade)?;
+        // Like values, masses are only tracked for all the materials together.
+        let mass = self.upgrade_mass / self.upgrade_materials.len() as f32;
+        self.upgrade_mass -= mass;
+        self.upgrade_materials.remove(idx);
+        Some(mass)
+    }
+
+    /// Puts a piece of `upgrade` material back in the hold, if there's room.
+    pub fn load_upgrade_material(&mut self, upgrade: &Upgrade, mass: f32) -> bool {
+        if mass > self.cargo_space_left() {
+            return false;
+        }
+        self.upgrade_mass += mass;
+        self.upgrade_materials.push(upgrade.clone());
+        true
+    }
+
     pub fn repair(&mut self, amount: f32) {
         let amount = amount / self.repair_cost_per_hull;
         self.shields = self.max_shields;
@@ -195,19 +391,54 @@ impl Player {
 }
 
 // Define a system to spawn the player.
-fn spawn_player(mut commands: Commands, skeletons: Res<Skeletons>) {
+fn spawn_player(
+    mut commands: Commands,
+    skeletons: Res<Skeletons>,
+    hulls: Res<HullCatalogue>,
+    existing_players: Query<(), With<Player>>,
+) {
+    // A resumed career has already spawned its ship.
+    if !existing_players.is_empty() {
+        return;
+    }
+    let player = Player::with_hull(hulls.for_player(&Player::new()));
+    spawn_player_ship(&mut commands, &skeletons, &hulls, player, Vec2::ZERO);
+}
+
+pub fn spawn_player_ship(
+    commands: &mut Commands,
+    skeletons: &Skeletons,
+    hulls: &HullCatalogue,
+    player: Player,
+    system_location: Vec2,
+) {
+    let hull = hulls.for_player(&player);
     // Spawn a sprite for the player.
     let mut transform = Transform::default();
-    transform.scale = Vec3::splat(0.5);
+    transform.scale = Vec3::splat(hull.scale);
     commands.spawn((
         SpineBundle {
-            skeleton: skeletons.player_ship.clone(),
+            skeleton: skeletons
+                .get(&hull.skeleton)
+                .unwrap_or(&skeletons.player_ship)
+                .clone(),
             transform,
             ..Default::default()
         },
-        InertiaVolume::new(1.0, 64.0),
-        Player::new(),
-        SystemLocation::new(Vec2::new(0.0, 0.0)),
+        InertiaVolume::new(hull.mass, hull.radius),
+        Solid {
+            restitution: 0.3,
+            takes_ram_damage: true,
+        },
+        CollisionLayers::new(
+            CollisionLayers::PLAYER,
+            CollisionLayers::CARGO_SHIP
+                | CollisionLayers::ENEMY_BULLET
+                | CollisionLayers::PICKUP
+                | CollisionLayers::FIGHTER,
+        ),
+        player,
+        SystemLocation::new(system_location),
         Jammable,
     ));
     commands.spawn((
@@ -240,11 +471,11 @@ fn player_camera_system(
 }
 
 fn player_movement_system(
-    time: Res<Time>,
+    fixed_time: Res<FixedTime>,
     mut players: Query<(&mut Player, &mut InertiaVolume)>,
     input: Res<Input<KeyCode>>,
 ) {
-    let dt = time.delta_seconds();
+    let dt = fixed_time.period.as_secs_f32();
     for (mut player, mut inertia) in players.iter_mut() {
         player.thrust = 0.0;
         player.side_braking = 0.0;
@@ -319,6 +550,7 @@ fn player_laser_aim_system(
     camera: Query<(&Camera, &GlobalTransform)>,
     mut players: Query<(&mut Player, &Transform, &InertiaVolume, &mut Spine)>,
     mut cursor_moved_events: EventReader<CursorMoved>,
+    hulls: Res<HullCatalogue>,
 ) {
     if camera.is_empty() || players.is_empty() {
         return;
@@ -332,11 +564,10 @@ fn player_laser_aim_system(
         .map(|v| v.origin.truncate())
     {
         for (mut player, location, my_inertia, mut spine) in players.iter_mut() {
-            let turrets = ["left_turret", "right_turret", "forward_turret"];
-            for turret_name in turrets.iter() {
+            for turret in hulls.for_player(&player).turrets.iter() {
                 rotate_towards_world_location(
                     &mut spine,
-                    *turret_name,
+                    &turret.bone,
                     location,
                     mouse_world_location,
                     my_inertia,
@@ -358,8 +589,8 @@ fn player_laser_fire_system(
     mut commands: Commands,
     time: Res<Time>,
     mut players: Query<(&mut Player, &Transform, &InertiaVolume, &Spine)>,
-    lasers: Res<Lasers>,
     game_assets: Res<GameAssets>,
+    hulls: Res<HullCatalogue>,
     input: Res<Input<MouseButton>>,
 ) {
     for (mut player, location, my_inertia, spine) in players.iter_mut() {
@@ -371,41 +602,18 @@ fn player_laser_fire_system(
                     settings: PlaybackSettings::DESPAWN,
                 });
                 player.main_cooldown = player.main_speed;
-                if player.aim_rotation < PI * 5. / 8. && player.aim_rotation > -PI * 5. / 8. {
-                    fire_laser_from_turret(
-                        "forward_turret",
-                        spine,
-                        location,
-                        my_inertia,
-                        &mut commands,
-                        lasers.player_laser_mesh.clone().into(),
-                        lasers.player_laser_material.clone(),
-                        Bullet::Player,
-                    );
-                }
-                if player.aim_rotation > -PI / 8. || player.aim_rotation < -PI * 7. / 8. {
-                    fire_laser_from_turret(
-                        "left_turret",
-                        spine,
-                        location,
-                        my_inertia,
-                        &mut commands,
-                        lasers.player_laser_mesh.clone().into(),
-                        lasers.player_laser_material.clone(),
-                        Bullet::Player,
-                    );
-                }
-                if player.aim_rotation < PI / 8. || player.aim_rotation > PI * 7. / 8. {
-                    fire_laser_from_turret(
-                        "right_turret",
-                        spine,
-                        location,
-                        my_inertia,
-                        &mut commands,
-                        lasers.player_laser_mesh.clone().into(),
-                        lasers.player_laser_material.clone(),
-                        Bullet::Player,
-                    );
+                for turret in hulls.for_player(&player).turrets.iter() {
+                    if turret.covers(player.aim_rotation) {
+                        fire_laser_from_turret(
+                            &turret.bone,
+                            spine,
+                            location,
+                            my_inertia,
+                            &mut commands,
+                            Bullet::player(),
+                            player.laser_damage(),
+                        );
+                    }
                 }
             }
         }
@@ -471,8 +679,9 @@ pub fn player_star_pixel_system(
     mut commands: Commands,
     mut players: Query<(&Player, &Transform, &InertiaVolume)>,
     lasers: Res<Lasers>,
+    mut rng: ResMut<GameRng>,
 ) {
-    let mut rng = rand::thread_rng();
+    let rng = &mut rng.cosmetic;
     let dt = time.delta_seconds();
     for (player, transform, inertia) in players.iter_mut() {
         let center = transform.translation;
@@ -491,7 +700,7 @@ pub fn player_star_pixel_system(
                     material: lasers.star_material.clone(),
                     ..Default::default()
                 },
-                SpacePixel::random_jammer(),
+                SpacePixel::random_jammer(rng),
             ));
         }
     }
@@ -1,16 +1,24 @@
 pub use crate::assets::{GameAssets, Lasers, Skeletons};
-pub use crate::bullets::Bullet;
+pub use crate::bullets::{Bullet, Owner};
+pub use crate::damage::Damage;
 pub use crate::game_state::GameState;
+pub use crate::hulls::{HullCatalogue, HullClass};
 pub use crate::indicators::{create_indicator_with_text, DistantIndicator};
 pub use crate::jamming::{Jammable, Jammed, Jammer};
-pub use crate::physics::{Collision, InertiaVolume};
-pub use crate::pickups::{spawn_exotic, spawn_salvage, spawn_upgrade, Pickup, Upgrade};
+pub use crate::physics::{Collision, CollisionLayers, InertiaVolume, Solid};
+pub use crate::pickups::{
+    spawn_exotic, spawn_loot, spawn_pickup, spawn_salvage, spawn_upgrade, Commodity, LootDrop,
+    Manifest, Pickup,
+};
 pub use crate::player::Player;
+pub use crate::rng::GameRng;
 pub use crate::space_pixels::SpacePixel;
 pub use crate::trade_routes::{
-    CargoSection, CargoShip, Regional, SystemLocation, ARENA_SIZE, HYPERDRIVE_SPEED,
+    CargoSection, CargoShip, Regional, ShipClass, ShipClassCatalogue, SystemLocation, ARENA_SIZE,
+    HYPERDRIVE_SPEED,
 };
 pub use crate::turrets::*;
+pub use crate::upgrades::{Upgrade, UpgradeCatalogue};
 use bevy::ecs::system::Command;
 pub use bevy::prelude::*;
 pub use bevy::utils::HashMap;
@@ -75,11 +83,13 @@ pub fn aim_ahead_location(
     }
 }
 
+/// How far to turn from `from` to face `to`, the short way round, between -PI and PI.
+pub fn angle_difference(from: f32, to: f32) -> f32 {
+    (to - from + PI).rem_euclid(2.0 * PI) - PI
+}
+
 pub fn rotations_match(rotation1: f32, rotation2: f32, leeway: f32) -> bool {
-    let rotation1 = rotation1 % (2.0 * PI);
-    let rotation2 = rotation2 % (2.0 * PI);
-    let diff = (rotation1 - rotation2).abs();
-    diff < leeway
+    angle_difference(rotation1, rotation2).abs() < leeway
 }
 
 #[cfg(test)]
@@ -91,4 +101,13 @@ mod prelude_tests {
         assert_eq!(rotations_match(0.0, 0.0, PI / 4.), true);
         assert_eq!(rotations_match(PI, 0.0, PI / 4.), false);
     }
+
+    #[test]
+    fn rotations_match_across_the_seam() {
+        assert!(rotations_match(-0.01, 0.01, 0.1));
+        assert!(rotations_match(PI * 2. - 0.01, 0.01, 0.1));
+        assert!(rotations_match(PI - 0.01, -PI + 0.01, 0.1));
+        assert!((angle_difference(-0.01, 0.01) - 0.02).abs() < 0.0001);
+        assert!((angle_difference(0.01, -0.01) + 0.02).abs() < 0.0001);
+    }
 }
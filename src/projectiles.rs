@@ -0,0 +1,300 @@
+use bevy::{ecs::system::Command, hierarchy::despawn_with_children_recursive};
+
+use crate::{
+    bullets::Laser,
+    physics::{PhysicsInterpolation, PhysicsSet, SpacialReference},
+    prelude::*,
+};
+
+pub struct ProjectilesPlugin;
+
+impl Plugin for ProjectilesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LaserPool>()
+            .init_resource::<ProjectileStats>()
+            .add_systems(
+                FixedUpdate,
+                expire_projectiles
+                    .run_if(not(in_state(GameState::Loading)))
+                    .in_set(PhysicsSet::Resolve),
+            );
+    }
+}
+
+// Well past anything a turret aims at, but gone before it's worth tracking.
+const LASER_RANGE: f32 = 2000.0;
+const LASER_LIFETIME: f32 = 3.0;
+// Past this many idle lasers, retired ones are despawned instead.
+const MAX_POOLED_LASERS: usize = 256;
+
+/// How much further a projectile can go before it fizzles out, whichever runs out first.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Projectile {
+    /// Seconds left.
+    pub lifetime: f32,
+    /// World units left to travel.
+    pub range: f32,
+}
+
+impl Projectile {
+    pub fn new(lifetime: f32, range: f32) -> Self {
+        Self { lifetime, range }
+    }
+
+    /// A projectile that only runs out of time, however far it goes.
+    pub fn lifetime(lifetime: f32) -> Self {
+        Self::new(lifetime, f32::INFINITY)
+    }
+
+    pub fn laser() -> Self {
+        Self::new(LASER_LIFETIME, LASER_RANGE)
+    }
+
+    pub fn expired(&self) -> bool {
+        self.lifetime <= 0. || self.range <= 0.
+    }
+}
+
+/// Retired lasers, hidden and out of physics, waiting to be fired again.
+#[derive(Resource, Debug, Default)]
+pub struct LaserPool {
+    idle: Vec<Entity>,
+}
+
+/// Projectile counts, for profiling.
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct ProjectileStats {
+    /// Projectiles in flight on each side, as of the last physics tick.
+    pub player: usize,
+    pub enemy: usize,
+    /// Lasers waiting in the pool.
+    pub pooled: usize,
+    /// Lasers fired so far, and how many of those came out of the pool.
+    pub fired: usize,
+    pub reused: usize,
+    /// Projectiles that ran out of range or time so far.
+    pub expired: usize,
+}
+
+impl ProjectileStats {
+    pub fn live(&self) -> usize {
+        self.player + self.enemy
+    }
+}
+
+/// A [`Command`] that fires a laser, reusing one from the [`LaserPool`] if there are any.
+pub struct SpawnLaser {
+    pub transform: Transform,
+    pub inertia: InertiaVolume,
+    pub bullet: Bullet,
+    pub damage: Damage,
+}
+
+impl Command for SpawnLaser {
+    fn apply(self, world: &mut World) {
+        let bundle = (
+            SpatialBundle {
+                transform: self.transform,
+                // Collisions are checked before transforms propagate, so don't start at the origin.
+                global_transform: self.transform.into(),
+                ..Default::default()
+            },
+            self.inertia,
+            self.bullet.collision_layers(),
+            self.bullet,
+            self.damage,
+            Projectile::laser(),
+            Laser,
+            Regional,
+        );
+        let pooled = loop {
+            match world
+                .get_resource_mut::<LaserPool>()
+                .and_then(|mut pool| pool.idle.pop())
+            {
+                Some(entity) if world.get_entity(entity).is_some() => break Some(entity),
+                // Despawned while idle, so try the next one.
+                Some(_) => continue,
+                None => break None,
+            }
+        };
+        match pooled {
+            Some(entity) => {
+                world.entity_mut(entity).insert(bundle);
+            }
+            None => {
+                world.spawn(bundle);
+            }
+        }
+        let idle = world
+            .get_resource::<LaserPool>()
+            .map_or(0, |pool| pool.idle.len());
+        if let Some(mut stats) = world.get_resource_mut::<ProjectileStats>() {
+            stats.fired += 1;
+            if pooled.is_some() {
+                stats.reused += 1;
+            }
+            stats.pooled = idle;
+        }
+    }
+}
+
+/// A [`Command`] that takes a projectile out of play. Lasers go back to the [`LaserPool`]
+/// while there's room, and anything else is despawned.
+pub struct RetireProjectile(pub Entity);
+
+impl Command for RetireProjectile {
+    fn apply(self, world: &mut World) {
+        let Some(projectile) = world.get_entity(self.0) else {
+            return;
+        };
+        // Hitting two things in one tick retires it twice.
+        if !projectile.contains::<Bullet>() {
+            return;
+        }
+        let poolable = projectile.contains::<Laser>()
+            && world
+                .get_resource::<LaserPool>()
+                .map_or(false, |pool| pool.idle.len() < MAX_POOLED_LASERS);
+        if poolable {
+            // Keep the mesh and material, but drop out of physics and regions.
+            world
+                .entity_mut(self.0)
+                .remove::<(
+                    Bullet,
+                    Damage,
+                    Laser,
+                    Projectile,
+                    InertiaVolume,
+                    CollisionLayers,
+                    SpacialReference,
+                    PhysicsInterpolation,
+                    Regional,
+                )>()
+                .insert(Visibility::Hidden);
+            let mut pool = world.resource_mut::<LaserPool>();
+            pool.idle.push(self.0);
+            let idle = pool.idle.len();
+            if let Some(mut stats) = world.get_resource_mut::<ProjectileStats>() {
+                stats.pooled = idle;
+            }
+        } else {
+            despawn_with_children_recursive(world, self.0);
+        }
+    }
+}
+
+fn expire_projectiles(
+    fixed_time: Res<FixedTime>,
+    mut commands: Commands,
+    mut projectiles: Query<(Entity, &Bullet, &InertiaVolume, &mut Projectile)>,
+    mut stats: ResMut<ProjectileStats>,
+) {
+    let dt = fixed_time.period.as_secs_f32();
+    stats.player = 0;
+    stats.enemy = 0;
+    for (entity, bullet, inertia, mut projectile) in projectiles.iter_mut() {
+        projectile.lifetime -= dt;
+        projectile.range -= inertia.velocity.length() * dt;
+        if projectile.expired() {
+            stats.expired += 1;
+            commands.add(RetireProjectile(entity));
+            continue;
+        }
+        match bullet.owner {
+            Owner::Player => stats.player += 1,
+            Owner::Enemy => stats.enemy += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod projectiles_tests {
+    use super::*;
+    use crate::{bullets::spawn_laser, test_harness::Simulation, trade_routes::spawn_cargo_ship};
+
+    fn fire(commands: &mut Commands, speed: f32) {
+        let mut inertia = InertiaVolume::new(1.0, 1.0);
+        inertia.velocity = Vec2::new(0.0, speed);
+        spawn_laser(
+            commands,
+            Transform::default(),
+            inertia,
+            Bullet::player(),
+            Player::new().laser_damage(),
+        );
+    }
+
+    #[test]
+    fn missed_lasers_expire_back_into_the_pool() {
+        let mut simulation = Simulation::new();
+        let dt = simulation
+            .app
+            .world
+            .resource::<FixedTime>()
+            .period
+            .as_secs_f32();
+        // Out of range halfway through the 101st tick, long before it's out of time.
+        let speed = LASER_RANGE / (100.5 * dt);
+        simulation.spawn(|commands| fire(commands, speed));
+        simulation.step(100);
+        let stats = simulation.app.world.resource::<ProjectileStats>();
+        assert_eq!((stats.live(), stats.pooled), (1, 0));
+
+        simulation.step(1);
+        let stats = simulation.app.world.resource::<ProjectileStats>();
+        assert_eq!(stats.live(), 0);
+        assert_eq!(stats.expired, 1);
+        assert_eq!(stats.pooled, 1);
+
+        simulation.spawn(|commands| fire(commands, LASER_SPEED));
+        simulation.step(1);
+        let stats = simulation.app.world.resource::<ProjectileStats>();
+        assert_eq!((stats.live(), stats.pooled), (1, 0));
+        assert_eq!((stats.fired, stats.reused), (2, 1));
+        let mut lasers = simulation.app.world.query::<&Laser>();
+        assert_eq!(lasers.iter(&simulation.app.world).count(), 1);
+    }
+
+    #[test]
+    fn pooled_lasers_only_hit_once() {
+        let mut simulation = Simulation::new();
+        let cargo_ship = simulation.spawn(|commands| {
+            spawn_cargo_ship(
+                commands,
+                Transform::default(),
+                InertiaVolume::new(10000.0, 0.0),
+                CargoShip::new(&ShipClass::freighter(), Manifest::Ore, 1.0),
+            )
+        });
+        simulation.step(1);
+        let dt = simulation
+            .app
+            .world
+            .resource::<FixedTime>()
+            .period
+            .as_secs_f32();
+        // Up through the first section at (-144, -16) and into the one behind it at
+        // (-144, 16), both within a single tick.
+        let mut inertia = InertiaVolume::new(1.0, 1.0);
+        inertia.velocity = Vec2::new(0.0, 100.0 / dt);
+        simulation.spawn(|commands| {
+            spawn_laser(
+                commands,
+                Transform::from_xyz(-144.0, -80.0, 0.0),
+                inertia,
+                Bullet::player(),
+                Player::new().laser_damage(),
+            )
+        });
+        simulation.step(1);
+        let stats = simulation.app.world.resource::<ProjectileStats>();
+        assert_eq!(stats.pooled, 1);
+        let cargo_ship = simulation.get::<CargoShip>(cargo_ship).unwrap();
+        assert_eq!(
+            cargo_ship.section_health(0),
+            75.0 - Player::new().laser_damage().amount
+        );
+        assert_eq!(cargo_ship.section_health(4), 75.0);
+    }
+}
@@ -0,0 +1,138 @@
+use rand_pcg::Pcg64;
+
+use crate::prelude::*;
+
+pub const SEED_ENV_VAR: &str = "SPACE_PIRACY_SEED";
+const SEED_ARG: &str = "--seed";
+
+const WORLD_STREAM: u128 = 0x5741_524c_44;
+const LOOT_STREAM: u128 = 0x4c4f_4f54;
+const MARKET_STREAM: u128 = 0x4d41_524b_4554;
+const COSMETIC_STREAM: u128 = 0x434f_534d_4554_4943;
+const TRAFFIC_STREAM: u128 = 0x5452_4146_4649_43;
+
+pub struct GameRngPlugin;
+
+impl Plugin for GameRngPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(GameRng::from_environment());
+    }
+}
+
+/// All randomness in a run comes from here, so a seed reproduces the run.
+/// Each stream is independent, so e.g. extra star pixels at a high frame rate
+/// never change which upgrades drop.
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    // Star map, asteroid fields and cargo ship spawns.
+    pub world: Pcg64,
+    // Salvage and upgrade drops.
+    pub loot: Pcg64,
+    // Day to day commodity prices.
+    pub market: Pcg64,
+    // Purely visual effects.
+    pub cosmetic: Pcg64,
+    // Convoys coming and going between systems.
+    pub traffic: Pcg64,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self::resume(seed, 0)
+    }
+
+    /// Picks a saved career back up on `day`. The day is mixed into every stream, so a
+    /// resumed career doesn't replay the rolls it started with.
+    pub fn resume(seed: u64, day: u32) -> Self {
+        let state = seed as u128 | (day as u128) << 64;
+        Self {
+            seed,
+            world: Pcg64::new(state, WORLD_STREAM),
+            loot: Pcg64::new(state, LOOT_STREAM),
+            market: Pcg64::new(state, MARKET_STREAM),
+            cosmetic: Pcg64::new(state, COSMETIC_STREAM),
+            traffic: Pcg64::new(state, TRAFFIC_STREAM),
+        }
+    }
+
+    /// Seeds from `--seed <seed>` or `SPACE_PIRACY_SEED`, falling back to a random seed.
+    pub fn from_environment() -> Self {
+        let seed = seed_from_args(std::env::args())
+            .or_else(|| {
+                std::env::var(SEED_ENV_VAR)
+                    .ok()
+                    .map(|seed| parse_seed(&seed))
+            })
+            .unwrap_or_else(|| rand::thread_rng().gen());
+        info!("Using seed {}", seed);
+        Self::new(seed)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+fn seed_from_args(mut args: impl Iterator<Item = String>) -> Option<u64> {
+    while let Some(arg) = args.next() {
+        if arg == SEED_ARG {
+            return args.next().map(|seed| parse_seed(&seed));
+        } else if let Some(seed) = arg.strip_prefix("--seed=") {
+            return Some(parse_seed(seed));
+        }
+    }
+    None
+}
+
+/// Numbers are used as-is. Anything else (like a date for a daily challenge) is hashed.
+fn parse_seed(seed: &str) -> u64 {
+    seed.trim().parse().unwrap_or_else(|_| {
+        // FNV-1a, so the same text gives the same seed on every platform.
+        seed.trim()
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+            })
+    })
+}
+
+#[cfg(test)]
+mod rng_tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_streams() {
+        let mut a = GameRng::new(2444);
+        let mut b = GameRng::new(2444);
+        for _ in 0..10 {
+            assert_eq!(a.world.gen::<u64>(), b.world.gen::<u64>());
+            assert_eq!(a.loot.gen::<u64>(), b.loot.gen::<u64>());
+        }
+    }
+
+    #[test]
+    fn resuming_moves_on_from_the_start() {
+        let mut fresh = GameRng::new(2444);
+        let mut resumed = GameRng::resume(2444, 3);
+        assert_eq!(resumed.seed(), 2444);
+        assert_ne!(fresh.loot.gen::<u64>(), resumed.loot.gen::<u64>());
+        assert_ne!(fresh.market.gen::<u64>(), resumed.market.gen::<u64>());
+    }
+
+    #[test]
+    fn streams_are_independent() {
+        let mut rng = GameRng::new(2444);
+        assert_ne!(rng.world.gen::<u64>(), rng.loot.gen::<u64>());
+    }
+
+    #[test]
+    fn seed_arguments() {
+        let args = ["game", "--seed", "42"].iter().map(|arg| arg.to_string());
+        assert_eq!(seed_from_args(args), Some(42));
+        let args = ["game", "--seed=42"].iter().map(|arg| arg.to_string());
+        assert_eq!(seed_from_args(args), Some(42));
+        assert_eq!(parse_seed("2026-10-18"), parse_seed("2026-10-18"));
+        assert_ne!(parse_seed("2026-10-18"), parse_seed("2026-10-19"));
+    }
+}
@@ -0,0 +1,270 @@
+use anyhow::{bail, Context};
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    heat::Heat,
+    home::{Career, HomeInSystem},
+    player::spawn_player_ship,
+    prelude::*,
+    trade_routes::{
+        spawn_galaxy, AsteroidsInSystem, CargoShipsInSystem, CurrentSystemRegion, Galaxy,
+        GalaxySystem, Lanes, SystemKind, SystemName,
+    },
+};
+
+pub const SAVE_PATH: &str = "career.ron";
+// Bump whenever the layout of `SaveFile` changes.
+pub const SAVE_VERSION: u32 = 10;
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SaveStatus>()
+            .add_systems(OnEnter(GameState::Home), clear_save_status)
+            .add_systems(Update, save_career_system.run_if(in_state(GameState::Home)));
+    }
+}
+
+/// Result of the last save attempt at the hideout, shown on the home screen.
+#[derive(Resource, Default)]
+pub struct SaveStatus(pub Option<String>);
+
+/// The career as of the last save at the hideout. What was left in each system, convoys and
+/// piracy records only last the session.
+#[derive(Serialize, Deserialize)]
+pub struct SaveFile {
+    pub version: u32,
+    pub seed: u64,
+    pub career: Career,
+    pub heat: f32,
+    pub player: Player,
+    pub player_location: [f32; 2],
+    pub systems: Vec<SavedSystem>,
+    pub lanes: Vec<(usize, usize)>,
+    pub current_system: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SavedSystem {
+    pub name: String,
+    pub location: [f32; 2],
+    pub kind: SystemKind,
+}
+
+// Only the version is read first, so old files fail with a clear message
+// instead of a confusing missing-field error.
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+pub fn write_save_file(path: &str, save: &SaveFile) -> anyhow::Result<()> {
+    let contents = ron::ser::to_string_pretty(save, PrettyConfig::default())
+        .context("Could not serialize career")?;
+    std::fs::write(path, contents).with_context(|| format!("Could not write {}", path))
+}
+
+pub fn read_save_file(path: &str) -> anyhow::Result<SaveFile> {
+    let contents =
+        std::fs::read_to_string(path).with_context(|| format!("No saved career at {}", path))?;
+    parse_save_file(&contents)
+}
+
+fn parse_save_file(contents: &str) -> anyhow::Result<SaveFile> {
+    let header: SaveHeader = ron::from_str(contents).context("Save file is corrupted")?;
+    if header.version != SAVE_VERSION {
+        bail!(
+            "Save file is version {}, but this game only reads version {}",
+            header.version,
+            SAVE_VERSION
+        );
+    }
+    ron::from_str(contents).context("Save file is corrupted")
+}
+
+fn clear_save_status(mut save_status: ResMut<SaveStatus>) {
+    save_status.0 = None;
+}
+
+fn save_career_system(
+    keys: Res<Input<KeyCode>>,
+    career: Res<Career>,
+    heat: Res<Heat>,
+    rng: Res<GameRng>,
+    players: Query<(&Player, &SystemLocation)>,
+    systems: Query<
+        (
+            Entity,
+            &SystemLocation,
+            &SystemName,
+            &Lanes,
+            Option<&AsteroidsInSystem>,
+            Option<&CargoShipsInSystem>,
+            Option<&HomeInSystem>,
+            Option<&CurrentSystemRegion>,
+        ),
+        Without<Player>,
+    >,
+    mut save_status: ResMut<SaveStatus>,
+) {
+    if !keys.just_pressed(KeyCode::F5) || players.is_empty() {
+        return;
+    }
+    let (player, player_location) = players.single();
+    let mut saved_systems = Vec::new();
+    let mut indices = HashMap::new();
+    let mut current_system = None;
+    for (entity, location, name, _, m_asteroids, m_ships, m_home, m_current) in systems.iter() {
+        if let Some(kind) = SystemKind::of(m_asteroids, m_ships, m_home) {
+            if m_current.is_some() {
+                current_system = Some(saved_systems.len());
+            }
+            indices.insert(entity, saved_systems.len());
+            saved_systems.push(SavedSystem {
+                name: name.0.clone(),
+                location: location.location.to_array(),
+                kind,
+            });
+        }
+    }
+    let mut lanes = Vec::new();
+    for (entity, _, _, system_lanes, ..) in systems.iter() {
+        for neighbour in system_lanes.0.iter() {
+            if let (Some(a), Some(b)) = (indices.get(&entity), indices.get(neighbour)) {
+                // Each lane is listed on both ends, only keep one.
+                if a < b {
+                    lanes.push((*a, *b));
+                }
+            }
+        }
+    }
+    let save = SaveFile {
+        version: SAVE_VERSION,
+        seed: rng.seed(),
+        career: career.clone(),
+        heat: heat.level,
+        player: player.clone(),
+        player_location: player_location.location.to_array(),
+        systems: saved_systems,
+        lanes,
+        current_system,
+    };
+    save_status.0 = Some(match write_save_file(SAVE_PATH, &save) {
+        Ok(()) => format!("Career saved to {}", SAVE_PATH),
+        Err(err) => format!("Save failed: {:#}", err),
+    });
+}
+
+/// Rebuilds the star map and the player's ship from a save.
+pub fn restore_career(
+    save: SaveFile,
+    commands: &mut Commands,
+    game_assets: &Res<GameAssets>,
+    skeletons: &Skeletons,
+    hulls: &HullCatalogue,
+    career: &mut Career,
+    heat: &mut Heat,
+    rng: &mut GameRng,
+) {
+    *rng = GameRng::resume(save.seed, save.career.days_survived);
+    *career = save.career;
+    heat.level = save.heat;
+    let galaxy = Galaxy {
+        systems: save
+            .systems
+            .into_iter()
+            .map(|system| GalaxySystem {
+                name: system.name,
+                location: Vec2::from_array(system.location),
+                kind: system.kind,
+            })
+            .collect(),
+        lanes: save.lanes,
+    };
+    let entities = spawn_galaxy(&galaxy, game_assets, commands);
+    if let Some(entity) = save.current_system.and_then(|idx| entities.get(idx)) {
+        commands.entity(*entity).insert(CurrentSystemRegion);
+    }
+    spawn_player_ship(
+        commands,
+        skeletons,
+        hulls,
+        save.player,
+        Vec2::from_array(save.player_location),
+    );
+}
+
+#[cfg(test)]
+mod save_tests {
+    use super::*;
+    use crate::home::Hideout;
+
+    #[test]
+    fn old_versions_are_rejected() {
+        let err = parse_save_file("(version: 0, seed: 1)").err().unwrap();
+        assert!(err.to_string().contains("version 0"));
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut hideout = Hideout::random(&mut GameRng::new(2444).world);
+        hideout.locker.exotic_material = 30.0;
+        let save = SaveFile {
+            version: SAVE_VERSION,
+            seed: 2444,
+            career: Career::default(),
+            heat: 12.5,
+            player: Player::new(),
+            player_location: [1.0, 2.0],
+            systems: vec![
+                SavedSystem {
+                    name: "Solara".to_string(),
+                    location: [3.0, 4.0],
+                    kind: SystemKind::Asteroids(6),
+                },
+                SavedSystem {
+                    name: "Vexion".to_string(),
+                    location: [5.0, 6.0],
+                    kind: SystemKind::Home(hideout.clone()),
+                },
+            ],
+            lanes: vec![(0, 1)],
+            current_system: Some(0),
+        };
+        let contents = ron::ser::to_string_pretty(&save, PrettyConfig::default()).unwrap();
+        let loaded = parse_save_file(&contents).unwrap();
+        assert_eq!(loaded.seed, 2444);
+        assert_eq!(loaded.heat, 12.5);
+        assert_eq!(loaded.systems[0].kind, SystemKind::Asteroids(6));
+        assert_eq!(loaded.systems[1].name, "Vexion");
+        assert_eq!(loaded.systems[1].kind, SystemKind::Home(hideout));
+        assert_eq!(loaded.lanes, vec![(0, 1)]);
+        assert_eq!(loaded.current_system, Some(0));
+    }
+
+    #[test]
+    fn visits_are_not_saved() {
+        let save = SaveFile {
+            version: SAVE_VERSION,
+            seed: 2444,
+            career: Career::default(),
+            heat: 0.0,
+            player: Player::new(),
+            player_location: [0.0, 0.0],
+            systems: vec![SavedSystem {
+                name: "Solara".to_string(),
+                location: [3.0, 4.0],
+                kind: SystemKind::Asteroids(6),
+            }],
+            lanes: vec![],
+            current_system: Some(0),
+        };
+        let contents = ron::ser::to_string_pretty(&save, PrettyConfig::default()).unwrap();
+        // Loaded careers regenerate these, so they'd be stale if they ever sneak in.
+        for session_only in ["snapshot", "convoy", "piracy"] {
+            assert!(!contents.to_lowercase().contains(session_only));
+        }
+    }
+}
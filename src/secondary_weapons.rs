@@ -0,0 +1,399 @@
+use bevy::sprite::Mesh2dHandle;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    damage::DamageType, effects::SoundEffect, fighters::Fighter, physics::PhysicsSet, prelude::*,
+    projectiles::Projectile,
+};
+
+pub struct SecondaryWeaponsPlugin;
+
+impl Plugin for SecondaryWeaponsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                fire_secondary_system.run_if(in_state(GameState::Playing)),
+                dress_ordnance.run_if(resource_exists::<Lasers>()),
+            ),
+        )
+        .add_systems(
+            FixedUpdate,
+            missile_guidance_system
+                .run_if(in_state(GameState::Playing))
+                .in_set(PhysicsSet::Forces),
+        );
+    }
+}
+
+/// Weapons fitted in a hull's secondary slots, fired with the right mouse button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SecondaryWeapon {
+    /// Homes in on whatever was under the cursor when fired.
+    Missiles,
+    /// Left behind the ship, going off when anything flies into them.
+    Mines,
+    /// Knocks out the turrets of every cargo ship nearby.
+    Emp,
+}
+
+/// What a secondary weapon costs to fit and keep loaded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeaponProfile {
+    /// What the shipyard charges to fit the launcher.
+    pub price: f32,
+    /// Rounds in each pack of ammo, bought or crafted.
+    pub pack_size: u32,
+    pub pack_price: f32,
+    /// XM used up crafting a pack instead.
+    pub pack_xm: f32,
+    pub max_ammo: u32,
+    /// Seconds between shots.
+    pub cooldown: f32,
+}
+
+impl SecondaryWeapon {
+    pub const ALL: [SecondaryWeapon; 3] = [
+        SecondaryWeapon::Missiles,
+        SecondaryWeapon::Mines,
+        SecondaryWeapon::Emp,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SecondaryWeapon::Missiles => "Missiles",
+            SecondaryWeapon::Mines => "Mines",
+            SecondaryWeapon::Emp => "EMP charges",
+        }
+    }
+
+    pub fn launcher_name(&self) -> &'static str {
+        match self {
+            SecondaryWeapon::Missiles => "Missile rack",
+            SecondaryWeapon::Mines => "Mine layer",
+            SecondaryWeapon::Emp => "EMP emitter",
+        }
+    }
+
+    pub fn profile(&self) -> WeaponProfile {
+        match self {
+            SecondaryWeapon::Missiles => WeaponProfile {
+                price: 600.0,
+                pack_size: 4,
+                pack_price: 150.0,
+                pack_xm: 20.0,
+                max_ammo: 12,
+                cooldown: 0.8,
+            },
+            SecondaryWeapon::Mines => WeaponProfile {
+                price: 400.0,
+                pack_size: 4,
+                pack_price: 100.0,
+                pack_xm: 15.0,
+                max_ammo: 12,
+                cooldown: 1.0,
+            },
+            SecondaryWeapon::Emp => WeaponProfile {
+                price: 900.0,
+                pack_size: 2,
+                pack_price: 200.0,
+                pack_xm: 30.0,
+                max_ammo: 4,
+                cooldown: 5.0,
+            },
+        }
+    }
+}
+
+/// A fitted secondary weapon and the rounds left for it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SecondarySlot {
+    pub weapon: SecondaryWeapon,
+    pub ammo: u32,
+}
+
+impl SecondarySlot {
+    /// Adds a pack of ammo, up to what the launcher holds, returning whether any fit.
+    pub fn reload(&mut self) -> bool {
+        let profile = self.weapon.profile();
+        if self.ammo >= profile.max_ammo {
+            return false;
+        }
+        self.ammo = (self.ammo + profile.pack_size).min(profile.max_ammo);
+        true
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct Missile {
+    pub target: Option<Entity>,
+}
+
+#[derive(Component, Debug)]
+pub struct Mine;
+
+// Shaped charges, to punch through armour.
+const MISSILE_DAMAGE: Damage = Damage {
+    amount: 30.0,
+    damage_type: DamageType::Kinetic,
+    penetration: 0.5,
+};
+const MISSILE_RADIUS: f32 = 4.0;
+const MISSILE_LAUNCH_SPEED: f32 = 200.0;
+const MISSILE_SPEED: f32 = 800.0;
+const MISSILE_ACCELERATION: f32 = 900.0;
+const MISSILE_TURN_RATE: f32 = 3.0;
+// Seconds of flight before a missile burns out.
+const MISSILE_FUEL: f32 = 6.0;
+// Missiles lock on to the closest target to the cursor within this angle and range.
+const MISSILE_LOCK_ANGLE: f32 = PI / 6.;
+const MISSILE_LOCK_RANGE: f32 = 1500.0;
+const MINE_DAMAGE: Damage = Damage {
+    amount: 40.0,
+    damage_type: DamageType::Kinetic,
+    penetration: 0.0,
+};
+// Anything this close sets a mine off.
+const MINE_TRIGGER_RADIUS: f32 = 48.0;
+// Seconds before a mine disarms itself.
+const MINE_LIFETIME: f32 = 60.0;
+const EMP_RADIUS: f32 = 800.0;
+const EMP_DURATION: f32 = 6.0;
+
+fn ordnance_bundle(transform: Transform, inertia: InertiaVolume, damage: Damage) -> impl Bundle {
+    let bullet = Bullet::player();
+    (
+        SpatialBundle {
+            transform,
+            global_transform: transform.into(),
+            ..Default::default()
+        },
+        inertia,
+        bullet.collision_layers(),
+        bullet,
+        damage,
+        Regional,
+    )
+}
+
+/// Spawns a player missile without any visuals, homing in on `target` if there is one.
+pub fn spawn_missile(
+    commands: &mut Commands,
+    transform: Transform,
+    inertia: InertiaVolume,
+    target: Option<Entity>,
+) -> Entity {
+    commands
+        .spawn((
+            ordnance_bundle(transform, inertia, MISSILE_DAMAGE),
+            Missile { target },
+            Projectile::lifetime(MISSILE_FUEL),
+        ))
+        .id()
+}
+
+/// Spawns a player mine without any visuals, sitting still at `translation`.
+pub fn spawn_mine(commands: &mut Commands, translation: Vec3) -> Entity {
+    commands
+        .spawn((
+            ordnance_bundle(
+                Transform::from_translation(translation),
+                InertiaVolume::new(1.0, MINE_TRIGGER_RADIUS),
+                MINE_DAMAGE,
+            ),
+            Mine,
+            Projectile::lifetime(MINE_LIFETIME),
+        ))
+        .id()
+}
+
+fn dress_ordnance(
+    mut commands: Commands,
+    missiles: Query<Entity, Added<Missile>>,
+    mines: Query<Entity, Added<Mine>>,
+    lasers: Res<Lasers>,
+) {
+    for entity in missiles.iter() {
+        commands.add(InsertSafe {
+            entity,
+            bundle: (
+                Mesh2dHandle(lasers.missile_mesh.clone()),
+                lasers.missile_material.clone(),
+            ),
+        });
+    }
+    for entity in mines.iter() {
+        commands.add(InsertSafe {
+            entity,
+            bundle: (
+                Mesh2dHandle(lasers.mine_mesh.clone()),
+                lasers.mine_material.clone(),
+            ),
+        });
+    }
+}
+
+/// The target closest to where the player is aiming, if any are close enough to lock on.
+fn missile_target<'a>(
+    position: Vec2,
+    aim_rotation: f32,
+    targets: impl Iterator<Item = (Entity, &'a Transform)>,
+) -> Option<Entity> {
+    let aim = Vec2::from_angle(aim_rotation);
+    targets
+        .filter_map(|(entity, transform)| {
+            let delta = transform.translation.truncate() - position;
+            let angle = aim.angle_between(delta).abs();
+            (delta.length() <= MISSILE_LOCK_RANGE && angle <= MISSILE_LOCK_ANGLE)
+                .then_some((entity, angle))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(entity, _)| entity)
+}
+
+fn fire_secondary_system(
+    mut cooldown: Local<f32>,
+    time: Res<Time>,
+    mut commands: Commands,
+    mut players: Query<(&mut Player, &Transform, &InertiaVolume)>,
+    targets: Query<(Entity, &Transform), (Or<(With<CargoShip>, With<Fighter>)>, Without<Player>)>,
+    mut cargo_ships: Query<(&mut CargoShip, &Transform), Without<Player>>,
+    mouse: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
+    mut sound_effects: EventWriter<SoundEffect>,
+) {
+    let Ok((mut player, transform, inertia)) = players.get_single_mut() else {
+        return;
+    };
+    *cooldown -= time.delta_seconds();
+    if keys.just_pressed(KeyCode::R) && !player.secondaries.is_empty() {
+        player.selected_secondary = (player.selected_secondary + 1) % player.secondaries.len();
+    }
+    if !mouse.just_pressed(MouseButton::Right) || *cooldown > 0. {
+        return;
+    }
+    let selected = player.selected_secondary;
+    let Some(slot) = player.secondaries.get_mut(selected) else {
+        sound_effects.send(SoundEffect::Fail);
+        return;
+    };
+    if slot.ammo == 0 {
+        sound_effects.send(SoundEffect::Fail);
+        return;
+    }
+    slot.ammo -= 1;
+    *cooldown = slot.weapon.profile().cooldown;
+    let position = transform.translation.truncate();
+    let aim_rotation = player.aim_rotation + inertia.rotation();
+    match slot.weapon {
+        SecondaryWeapon::Missiles => {
+            sound_effects.send(SoundEffect::CargoShipLaser);
+            let direction = Vec2::from_angle(aim_rotation);
+            let mut missile_inertia = InertiaVolume::new(1.0, MISSILE_RADIUS);
+            missile_inertia.velocity = inertia.velocity + direction * MISSILE_LAUNCH_SPEED;
+            missile_inertia.set_rotation(aim_rotation);
+            let mut missile_transform =
+                Transform::from_translation((position + direction * inertia.radius).extend(10.0));
+            missile_transform.rotation = Quat::from_rotation_z(aim_rotation);
+            spawn_missile(
+                &mut commands,
+                missile_transform,
+                missile_inertia,
+                missile_target(position, aim_rotation, targets.iter()),
+            );
+        }
+        SecondaryWeapon::Mines => {
+            sound_effects.send(SoundEffect::DeployJammer);
+            let behind = -Vec2::from_angle(inertia.rotation()) * inertia.radius;
+            spawn_mine(&mut commands, (position + behind).extend(0.0));
+        }
+        SecondaryWeapon::Emp => {
+            sound_effects.send(SoundEffect::CargoShipHyperdrive);
+            for (mut cargo_ship, cargo_ship_transform) in cargo_ships.iter_mut() {
+                if cargo_ship_transform
+                    .translation
+                    .truncate()
+                    .distance(position)
+                    <= EMP_RADIUS
+                {
+                    cargo_ship.freeze_turrets(EMP_DURATION);
+                }
+            }
+        }
+    }
+}
+
+fn missile_guidance_system(
+    fixed_time: Res<FixedTime>,
+    mut missiles: Query<(&Missile, &Transform, &mut InertiaVolume)>,
+    targets: Query<(&Transform, &InertiaVolume), Without<Missile>>,
+) {
+    let dt = fixed_time.period.as_secs_f32();
+    for (missile, transform, mut inertia) in missiles.iter_mut() {
+        let position = transform.translation.truncate();
+        let heading = inertia
+            .velocity
+            .try_normalize()
+            .unwrap_or_else(|| Vec2::from_angle(inertia.rotation()));
+        // Lead the target, or fly straight on if it's gone.
+        let desired = match missile.target.and_then(|target| targets.get(target).ok()) {
+            Some((target_transform, target_inertia)) => {
+                let delta = target_transform.translation.truncate() - position;
+                aim_ahead_location(position, delta, target_inertia.velocity, MISSILE_SPEED)
+                    .unwrap_or(position + delta)
+                    - position
+            }
+            None => heading,
+        };
+        let turn = heading
+            .angle_between(desired)
+            .clamp(-MISSILE_TURN_RATE * dt, MISSILE_TURN_RATE * dt);
+        let direction = Vec2::from_angle(turn).rotate(heading);
+        let speed = (inertia.velocity.length() + MISSILE_ACCELERATION * dt).min(MISSILE_SPEED);
+        inertia.velocity = direction * speed;
+        inertia.set_rotation(direction.y.atan2(direction.x));
+    }
+}
+
+#[cfg(test)]
+mod secondary_weapons_tests {
+    use super::*;
+    use crate::{fighters::fighter_bundle, test_harness::Simulation};
+
+    #[test]
+    fn missiles_chase_down_their_target() {
+        let mut simulation = Simulation::new();
+        let fighter = simulation.spawn(|commands| {
+            commands
+                .spawn(fighter_bundle(
+                    Transform::from_xyz(600.0, 300.0, 0.0),
+                    InertiaVolume::new(2.0, 24.0),
+                    Fighter::new(100.0, 0.0),
+                ))
+                .id()
+        });
+        // Fired straight up, well away from the fighter.
+        let mut inertia = InertiaVolume::new(1.0, MISSILE_RADIUS);
+        inertia.velocity = Vec2::new(0.0, MISSILE_LAUNCH_SPEED);
+        inertia.set_rotation(PI / 2.);
+        let missile = simulation.spawn(|commands| {
+            spawn_missile(commands, Transform::default(), inertia, Some(fighter))
+        });
+        simulation.step(180);
+        assert!(simulation.get::<Missile>(missile).is_none());
+        let fighter = simulation.get::<Fighter>(fighter).unwrap();
+        assert_eq!(fighter.hull, 100.0 - MISSILE_DAMAGE.amount);
+    }
+
+    #[test]
+    fn slots_hold_a_limited_amount_of_ammo() {
+        let mut slot = SecondarySlot {
+            weapon: SecondaryWeapon::Emp,
+            ammo: 0,
+        };
+        assert!(slot.reload());
+        assert!(slot.reload());
+        assert_eq!(slot.ammo, SecondaryWeapon::Emp.profile().max_ammo);
+        assert!(!slot.reload());
+    }
+}
@@ -0,0 +1,1044 @@
+use bevy::text::DEFAULT_FONT_HANDLE;
+
+use crate::{
+    heat::Heat,
+    home::{end_day, Career, Hideout, HomeInSystem, LAST_DAY},
+    prelude::*,
+    save::SaveStatus,
+    secondary_weapons::{SecondarySlot, SecondaryWeapon},
+    trade_routes::{CurrentSystemRegion, SystemName},
+    upgrades::PlayerStat,
+};
+
+pub struct ShipyardPlugin;
+
+impl Plugin for ShipyardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HomeMenu>()
+            .add_systems(OnEnter(GameState::Home), open_home_menu)
+            .add_systems(OnExit(GameState::Home), close_home_menu)
+            .add_systems(
+                Update,
+                (handle_home_input, rebuild_home_menu)
+                    .chain()
+                    .run_if(in_state(GameState::Home)),
+            );
+    }
+}
+
+/// The data the shipyard sells from.
+#[derive(Clone, Copy)]
+pub struct Catalogues<'a> {
+    pub upgrades: &'a UpgradeCatalogue,
+    pub hulls: &'a HullCatalogue,
+}
+
+/// Something to do at the hideout, one for each row of the home screen.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HomeAction {
+    /// Buys into a locked hideout.
+    Unlock,
+    Sell(Commodity),
+    /// Repairs up to this much hull, as far as earnings stretch.
+    Repair(f32),
+    Install(Upgrade),
+    SellMaterial(Upgrade),
+    Buy(Upgrade),
+    /// Trades the ship in for a new hull, by id.
+    BuyHull(String),
+    Fit(SecondaryWeapon),
+    BuyAmmo(SecondaryWeapon),
+    /// Makes a pack of ammo from XM in the hold.
+    CraftAmmo(SecondaryWeapon),
+    StashXm,
+    TakeXm,
+    Stash(Upgrade),
+    Take(Upgrade),
+    Depart,
+}
+
+#[derive(Resource, Default)]
+struct HomeMenu {
+    selected: usize,
+    // Set whenever the screen needs rebuilding.
+    dirty: bool,
+}
+
+#[derive(Component)]
+struct HomeScreen;
+
+#[derive(Component)]
+struct HomeButton(usize);
+
+const REPAIR_STEP: f32 = 10.0;
+// The shipyard buys XM materials back for this share of the upgrade's price.
+const MATERIAL_RESALE: f32 = 0.5;
+// And takes the old ship off your hands for this share of its hull's price.
+const HULL_TRADE_IN: f32 = 0.5;
+
+fn distinct<'a>(upgrades: impl Iterator<Item = &'a Upgrade>) -> Vec<Upgrade> {
+    let mut distinct: Vec<Upgrade> = Vec::new();
+    for upgrade in upgrades {
+        if !distinct.contains(upgrade) {
+            distinct.push(upgrade.clone());
+        }
+    }
+    distinct
+}
+
+/// Every row of the home screen, from what's in the hold, what's in the locker and what
+/// the shipyard sells.
+pub fn home_actions(player: &Player, hideout: &Hideout, catalogues: Catalogues) -> Vec<HomeAction> {
+    if !hideout.unlocked {
+        return vec![HomeAction::Unlock, HomeAction::Depart];
+    }
+    let mut actions = Vec::new();
+    for commodity in Commodity::ALL {
+        if player.commodities.get(&commodity).copied().unwrap_or(0.) > 0. {
+            actions.push(HomeAction::Sell(commodity));
+        }
+    }
+    let missing_hull = player.max_hull - player.hull;
+    if missing_hull > REPAIR_STEP {
+        actions.push(HomeAction::Repair(REPAIR_STEP));
+    }
+    if missing_hull > 0. {
+        actions.push(HomeAction::Repair(missing_hull));
+    }
+    for upgrade in distinct(player.upgrade_materials.iter()) {
+        actions.push(HomeAction::Install(upgrade.clone()));
+        actions.push(HomeAction::SellMaterial(upgrade));
+    }
+    for definition in catalogues.upgrades.upgrades.iter() {
+        actions.push(HomeAction::Buy(Upgrade(definition.id.clone())));
+    }
+    for hull in catalogues.hulls.hulls.iter() {
+        if hull.id != player.hull_class {
+            actions.push(HomeAction::BuyHull(hull.id.clone()));
+        }
+    }
+    for slot in player.secondaries.iter() {
+        actions.push(HomeAction::BuyAmmo(slot.weapon));
+        actions.push(HomeAction::CraftAmmo(slot.weapon));
+    }
+    for weapon in SecondaryWeapon::ALL {
+        if !player.secondaries.iter().any(|slot| slot.weapon == weapon) {
+            actions.push(HomeAction::Fit(weapon));
+        }
+    }
+    if player.exotic_material > 0. {
+        actions.push(HomeAction::StashXm);
+    }
+    if hideout.locker.exotic_material > 0. {
+        actions.push(HomeAction::TakeXm);
+    }
+    for upgrade in distinct(player.upgrade_materials.iter()) {
+        actions.push(HomeAction::Stash(upgrade));
+    }
+    for upgrade in distinct(
+        hideout
+            .locker
+            .upgrade_materials
+            .iter()
+            .map(|(upgrade, _)| upgrade),
+    ) {
+        actions.push(HomeAction::Take(upgrade));
+    }
+    actions.push(HomeAction::Depart);
+    actions
+}
+
+impl HomeAction {
+    /// Carries out the action at `hideout`, returning whether anything happened.
+    pub fn perform(
+        &self,
+        player: &mut Player,
+        career: &mut Career,
+        hideout: &mut Hideout,
+        catalogues: Catalogues,
+    ) -> bool {
+        let upgrades = catalogues.upgrades;
+        match self {
+            HomeAction::Unlock => {
+                if hideout.unlocked || hideout.price > career.earnings {
+                    return false;
+                }
+                career.earnings -= hideout.price;
+                hideout.unlocked = true;
+                true
+            }
+            HomeAction::Sell(commodity) => career.sell_commodity(player, *commodity, hideout) > 0.,
+            HomeAction::Repair(hull) => {
+                let cost_per_hull = hideout.repair_cost_per_hull(player);
+                let hull = hull
+                    .min(player.max_hull - player.hull)
+                    .min(career.earnings / cost_per_hull);
+                if hull <= 0. {
+                    return false;
+                }
+                career.earnings -= hull * cost_per_hull;
+                player.repair(hull * player.repair_cost_per_hull);
+                true
+            }
+            HomeAction::Install(upgrade) => {
+                let Some(definition) = upgrades.get(upgrade) else {
+                    return false;
+                };
+                if player.unload_upgrade_material(upgrade).is_none() {
+                    return false;
+                }
+                player.apply_upgrade(definition);
+                true
+            }
+            HomeAction::SellMaterial(upgrade) => {
+                let Some(definition) = upgrades.get(upgrade) else {
+                    return false;
+                };
+                if player.unload_upgrade_material(upgrade).is_none() {
+                    return false;
+                }
+                career.earnings += definition.price * MATERIAL_RESALE;
+                true
+            }
+            HomeAction::Buy(upgrade) => match upgrades.get(upgrade) {
+                Some(definition) if definition.price <= career.earnings => {
+                    career.earnings -= definition.price;
+                    player.apply_upgrade(definition);
+                    true
+                }
+                _ => false,
+            },
+            HomeAction::BuyHull(id) => {
+                let Some(hull) = catalogues.hulls.get(id) else {
+                    return false;
+                };
+                let cost = hull_cost(player, hull, catalogues.hulls);
+                let refitted = player.refit(hull, upgrades);
+                let cargo = player.max_cargo - player.cargo_space_left();
+                if cost > career.earnings || cargo > refitted.max_cargo {
+                    return false;
+                }
+                career.earnings -= cost;
+                *player = refitted;
+                true
+            }
+            HomeAction::Fit(weapon) => {
+                let slots = catalogues.hulls.for_player(player).secondary_slots;
+                let price = weapon.profile().price;
+                if player.secondaries.len() >= slots || price > career.earnings {
+                    return false;
+                }
+                career.earnings -= price;
+                player.secondaries.push(SecondarySlot {
+                    weapon: *weapon,
+                    ammo: 0,
+                });
+                true
+            }
+            HomeAction::BuyAmmo(weapon) => {
+                let price = weapon.profile().pack_price;
+                match player
+                    .secondaries
+                    .iter_mut()
+                    .find(|slot| slot.weapon == *weapon)
+                {
+                    Some(slot) if price <= career.earnings && slot.reload() => {
+                        career.earnings -= price;
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            HomeAction::CraftAmmo(weapon) => {
+                let xm = weapon.profile().pack_xm;
+                if xm > player.exotic_material {
+                    return false;
+                }
+                match player
+                    .secondaries
+                    .iter_mut()
+                    .find(|slot| slot.weapon == *weapon)
+                {
+                    Some(slot) if slot.reload() => {
+                        player.exotic_material -= xm;
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            HomeAction::StashXm => {
+                if player.exotic_material <= 0. {
+                    return false;
+                }
+                hideout.locker.exotic_material += player.exotic_material;
+                player.exotic_material = 0.;
+                true
+            }
+            HomeAction::TakeXm => {
+                let amount = hideout
+                    .locker
+                    .exotic_material
+                    .min(player.cargo_space_left());
+                if amount <= 0. {
+                    return false;
+                }
+                hideout.locker.exotic_material -= amount;
+                player.exotic_material += amount;
+                true
+            }
+            HomeAction::Stash(upgrade) => match player.unload_upgrade_material(upgrade) {
+                Some(mass) => {
+                    hideout
+                        .locker
+                        .upgrade_materials
+                        .push((upgrade.clone(), mass));
+                    true
+                }
+                None => false,
+            },
+            HomeAction::Take(upgrade) => {
+                let Some(idx) = hideout
+                    .locker
+                    .upgrade_materials
+                    .iter()
+                    .position(|(stashed, _)| stashed == upgrade)
+                else {
+                    return false;
+                };
+                let mass = hideout.locker.upgrade_materials[idx].1;
+                if !player.load_upgrade_material(upgrade, mass) {
+                    return false;
+                }
+                hideout.locker.upgrade_materials.remove(idx);
+                true
+            }
+            HomeAction::Depart => false,
+        }
+    }
+
+    /// The ship and career as they'd be after the action, or `None` if it can't be done.
+    pub fn preview(
+        &self,
+        player: &Player,
+        career: &Career,
+        hideout: &Hideout,
+        catalogues: Catalogues,
+    ) -> Option<(Player, Career)> {
+        let mut player = player.clone();
+        let mut career = career.clone();
+        if self.perform(&mut player, &mut career, &mut hideout.clone(), catalogues) {
+            Some((player, career))
+        } else {
+            None
+        }
+    }
+
+    fn heading(&self) -> Option<&'static str> {
+        match self {
+            HomeAction::Unlock => Some("Hideout for sale"),
+            HomeAction::Sell(_) => Some("Market"),
+            HomeAction::Repair(_)
+            | HomeAction::Install(_)
+            | HomeAction::SellMaterial(_)
+            | HomeAction::Buy(_) => Some("Shipyard"),
+            HomeAction::BuyHull(_) => Some("Hulls"),
+            HomeAction::Fit(_) | HomeAction::BuyAmmo(_) | HomeAction::CraftAmmo(_) => {
+                Some("Armoury")
+            }
+            HomeAction::StashXm
+            | HomeAction::TakeXm
+            | HomeAction::Stash(_)
+            | HomeAction::Take(_) => Some("Locker"),
+            HomeAction::Depart => None,
+        }
+    }
+
+    fn label(
+        &self,
+        player: &Player,
+        career: &Career,
+        hideout: &Hideout,
+        catalogues: Catalogues,
+    ) -> String {
+        let upgrades = catalogues.upgrades;
+        match self {
+            HomeAction::Unlock => format!("Buy into this hideout for ${:.0}", hideout.price),
+            HomeAction::Sell(commodity) => {
+                let mass = player.commodities.get(commodity).copied().unwrap_or(0.);
+                format!(
+                    "Sell {:.0} {} for ${:.0}",
+                    mass.ceil(),
+                    commodity.name(),
+                    career.prices().quote(*commodity, mass)
+                        * player.commodity_quality(*commodity)
+                        * hideout.price_factor(*commodity)
+                )
+            }
+            HomeAction::Repair(hull) => {
+                let all = if *hull >= player.max_hull - player.hull {
+                    "all "
+                } else {
+                    ""
+                };
+                format!(
+                    "Repair {}{:.0} hull for ${:.0}",
+                    all,
+                    hull,
+                    hull * hideout.repair_cost_per_hull(player)
+                )
+            }
+            HomeAction::Install(upgrade) => format!(
+                "Install {} from {} ({} held)",
+                upgrades.name(upgrade),
+                upgrades.material_name(upgrade),
+                player
+                    .upgrade_materials
+                    .iter()
+                    .filter(|held| *held == upgrade)
+                    .count()
+            ),
+            HomeAction::SellMaterial(upgrade) => format!(
+                "Sell {} for ${:.0}",
+                upgrades.material_name(upgrade),
+                upgrades
+                    .get(upgrade)
+                    .map_or(0., |definition| definition.price * MATERIAL_RESALE)
+            ),
+            HomeAction::Buy(upgrade) => format!(
+                "Buy {} for ${:.0}",
+                upgrades.name(upgrade),
+                upgrades
+                    .get(upgrade)
+                    .map_or(0., |definition| definition.price)
+            ),
+            HomeAction::BuyHull(id) => match catalogues.hulls.get(id) {
+                Some(hull) => {
+                    let cost = hull_cost(player, hull, catalogues.hulls);
+                    if cost < 0. {
+                        format!("Trade down to a {} for ${:.0} back", hull.name, -cost)
+                    } else {
+                        format!("Trade up to a {} for ${:.0}", hull.name, cost)
+                    }
+                }
+                None => id.clone(),
+            },
+            HomeAction::Fit(weapon) => format!(
+                "Fit a {} for ${:.0}",
+                weapon.launcher_name(),
+                weapon.profile().price
+            ),
+            HomeAction::BuyAmmo(weapon) => format!(
+                "Buy {} {} for ${:.0}",
+                weapon.profile().pack_size,
+                weapon.name(),
+                weapon.profile().pack_price
+            ),
+            HomeAction::CraftAmmo(weapon) => format!(
+                "Craft {} {} from {:.0} XM",
+                weapon.profile().pack_size,
+                weapon.name(),
+                weapon.profile().pack_xm
+            ),
+            HomeAction::StashXm => format!(
+                "Stash {:.0} XM in the locker",
+                player.exotic_material.floor()
+            ),
+            HomeAction::TakeXm => format!(
+                "Take {:.0} XM from the locker",
+                hideout.locker.exotic_material.floor()
+            ),
+            HomeAction::Stash(upgrade) => {
+                format!("Stash {} in the locker", upgrades.material_name(upgrade))
+            }
+            HomeAction::Take(upgrade) => format!(
+                "Take {} from the locker ({} stashed)",
+                upgrades.material_name(upgrade),
+                hideout
+                    .locker
+                    .upgrade_materials
+                    .iter()
+                    .filter(|(stashed, _)| stashed == upgrade)
+                    .count()
+            ),
+            HomeAction::Depart if career.days_survived >= LAST_DAY => "Retire".to_string(),
+            HomeAction::Depart => "Depart".to_string(),
+        }
+    }
+}
+
+/// What a new `hull` costs once the player's current ship is traded in.
+fn hull_cost(player: &Player, hull: &HullClass, hulls: &HullCatalogue) -> f32 {
+    hull.price - hulls.for_player(player).price * HULL_TRADE_IN
+}
+
+/// What changes between two versions of the ship and career, a line each.
+fn describe_changes(before: (&Player, &Career), after: (&Player, &Career)) -> Vec<String> {
+    let mut changes = Vec::new();
+    if before.1.earnings != after.1.earnings {
+        changes.push(format!(
+            "Earnings: ${:.0} -> ${:.0}",
+            before.1.earnings, after.1.earnings
+        ));
+    }
+    if before.0.hull != after.0.hull {
+        changes.push(format!("Hull: {:.0} -> {:.0}", before.0.hull, after.0.hull));
+    }
+    if before.0.cargo_space_left() != after.0.cargo_space_left() {
+        changes.push(format!(
+            "Free cargo space: {:.0} -> {:.0}",
+            before.0.cargo_space_left(),
+            after.0.cargo_space_left()
+        ));
+    }
+    if before.0.exotic_material != after.0.exotic_material {
+        changes.push(format!(
+            "XM: {:.0} -> {:.0}",
+            before.0.exotic_material, after.0.exotic_material
+        ));
+    }
+    for slot in after.0.secondaries.iter() {
+        let ammo = before
+            .0
+            .secondaries
+            .iter()
+            .find(|before| before.weapon == slot.weapon)
+            .map(|before| before.ammo);
+        match ammo {
+            Some(ammo) if ammo == slot.ammo => {}
+            Some(ammo) => {
+                changes.push(format!("{}: {} -> {}", slot.weapon.name(), ammo, slot.ammo))
+            }
+            None => changes.push(format!("Fitted: {}", slot.weapon.launcher_name())),
+        }
+    }
+    for stat in PlayerStat::ALL {
+        if before.0.stat(stat) != after.0.stat(stat) {
+            changes.push(format!(
+                "{}: {:.2} -> {:.2}",
+                stat.name(),
+                before.0.stat(stat),
+                after.0.stat(stat)
+            ));
+        }
+    }
+    changes
+}
+
+/// Today's prices from the local fence, and how they've moved since yesterday.
+fn market_report(career: &Career, hideout: &Hideout) -> String {
+    let prices = career.prices();
+    let yesterday = career.yesterdays_prices();
+    Commodity::ALL
+        .iter()
+        .map(|commodity| {
+            let price = prices.price(*commodity) * hideout.price_factor(*commodity);
+            let previous = yesterday
+                .map(|yesterday| yesterday.price(*commodity) * hideout.price_factor(*commodity));
+            match previous {
+                Some(previous) if previous > 0. => format!(
+                    "{} ${:.1} ({:+.0}%)",
+                    commodity.name(),
+                    price,
+                    (price / previous - 1.) * 100.
+                ),
+                _ => format!("{} ${:.1}", commodity.name(), price),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("   ")
+}
+
+fn text_style(font_size: f32, color: Color) -> TextStyle {
+    TextStyle {
+        font: DEFAULT_FONT_HANDLE.typed(),
+        font_size,
+        color,
+    }
+}
+
+fn hideout_report(name: &str, hideout: &Hideout) -> String {
+    if hideout.unlocked {
+        format!(
+            "{}: repairs at {:.0}% of the usual price, the fence is after {}",
+            name,
+            hideout.repair_multiplier * 100.,
+            hideout.market_bias.name()
+        )
+    } else {
+        format!("{}: another crew's hideout, up for sale", name)
+    }
+}
+
+fn spawn_home_screen(
+    commands: &mut Commands,
+    player: &Player,
+    career: &Career,
+    (name, hideout): (&str, &Hideout),
+    catalogues: Catalogues,
+    save_status: &SaveStatus,
+    selected: usize,
+) {
+    let actions = home_actions(player, hideout, catalogues);
+    let header = format!(
+        "{}\nDay {}, {} days left\n{}   Earnings: ${:.0}   Hull: {:.0}/{:.0}   Cargo: {:.0}/{:.0}\n{}",
+        hideout_report(name, hideout),
+        career.days_survived,
+        LAST_DAY.saturating_sub(career.days_survived),
+        catalogues.hulls.for_player(player).name,
+        career.earnings,
+        player.hull,
+        player.max_hull,
+        player.max_cargo - player.cargo_space_left(),
+        player.max_cargo,
+        market_report(career, hideout)
+    );
+    let preview = match actions.get(selected) {
+        Some(HomeAction::Depart) | None => String::new(),
+        Some(action) => match action.preview(player, career, hideout, catalogues) {
+            Some((after_player, after_career)) => {
+                describe_changes((player, career), (&after_player, &after_career)).join("\n")
+            }
+            None => "Not enough earnings or cargo space for that.".to_string(),
+        },
+    };
+    let footer = format!(
+        "[Up], [Down] to choose, [Enter] or click to confirm, [Space] to {}\n{}",
+        if career.days_survived >= LAST_DAY {
+            "retire"
+        } else {
+            "depart"
+        },
+        save_status
+            .0
+            .as_deref()
+            .unwrap_or("Press [F5] to save your career")
+    );
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    display: Display::Flex,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    width: Val::Percent(100.),
+                    height: Val::Percent(100.),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            HomeScreen,
+        ))
+        .with_children(|root| {
+            root.spawn(NodeBundle {
+                style: Style {
+                    display: Display::Flex,
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(16.)),
+                    row_gap: Val::Px(2.),
+                    ..Default::default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.75).into(),
+                ..Default::default()
+            })
+            .with_children(|panel| {
+                panel.spawn(TextBundle::from_section(
+                    header,
+                    text_style(18., Color::WHITE),
+                ));
+                let mut last_heading = None;
+                for (idx, action) in actions.iter().enumerate() {
+                    if action.heading().is_some() && action.heading() != last_heading {
+                        last_heading = action.heading();
+                        panel.spawn(TextBundle::from_section(
+                            format!("\n{}", last_heading.unwrap()),
+                            text_style(20., Color::YELLOW_GREEN),
+                        ));
+                    }
+                    let possible = *action == HomeAction::Depart
+                        || action
+                            .preview(player, career, hideout, catalogues)
+                            .is_some();
+                    panel
+                        .spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    padding: UiRect::axes(Val::Px(8.), Val::Px(2.)),
+                                    ..Default::default()
+                                },
+                                background_color: if idx == selected {
+                                    Color::rgba(0.2, 0.3, 0.6, 1.0)
+                                } else {
+                                    Color::NONE
+                                }
+                                .into(),
+                                ..Default::default()
+                            },
+                            HomeButton(idx),
+                        ))
+                        .with_children(|button| {
+                            button.spawn(TextBundle::from_section(
+                                action.label(player, career, hideout, catalogues),
+                                text_style(16., if possible { Color::WHITE } else { Color::GRAY }),
+                            ));
+                        });
+                }
+                panel.spawn(TextBundle::from_section(
+                    format!("\n{}", preview),
+                    text_style(16., Color::YELLOW),
+                ));
+                panel.spawn(TextBundle::from_section(
+                    footer,
+                    text_style(14., Color::GRAY),
+                ));
+            });
+        });
+}
+
+fn open_home_menu(mut menu: ResMut<HomeMenu>) {
+    menu.selected = 0;
+    menu.dirty = true;
+}
+
+fn close_home_menu(mut commands: Commands, screens: Query<Entity, With<HomeScreen>>) {
+    for screen in screens.iter() {
+        commands.entity(screen).despawn_recursive();
+    }
+}
+
+fn handle_home_input(
+    mut commands: Commands,
+    keys: Res<Input<KeyCode>>,
+    buttons: Query<(&Interaction, &HomeButton), Changed<Interaction>>,
+    mut menu: ResMut<HomeMenu>,
+    mut players: Query<&mut Player>,
+    mut hideouts: Query<&mut HomeInSystem, With<CurrentSystemRegion>>,
+    mut career: ResMut<Career>,
+    mut heat: ResMut<Heat>,
+    mut rng: ResMut<GameRng>,
+    upgrades: Res<UpgradeCatalogue>,
+    hulls: Res<HullCatalogue>,
+    game_assets: Res<GameAssets>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let (Ok(mut player), Ok(mut home)) = (players.get_single_mut(), hideouts.get_single_mut())
+    else {
+        return;
+    };
+    let catalogues = Catalogues {
+        upgrades: &upgrades,
+        hulls: &hulls,
+    };
+    let actions = home_actions(&player, &home.0, catalogues);
+    let mut activated = None;
+    for (interaction, button) in buttons.iter() {
+        match interaction {
+            Interaction::Pressed => activated = Some(button.0),
+            Interaction::Hovered if menu.selected != button.0 => {
+                menu.selected = button.0;
+                menu.dirty = true;
+            }
+            _ => {}
+        }
+    }
+    if keys.any_just_pressed([KeyCode::Up, KeyCode::W]) {
+        menu.selected = (menu.selected + actions.len() - 1) % actions.len();
+        menu.dirty = true;
+    }
+    if keys.any_just_pressed([KeyCode::Down, KeyCode::S]) {
+        menu.selected = (menu.selected + 1) % actions.len();
+        menu.dirty = true;
+    }
+    if keys.just_pressed(KeyCode::Return) {
+        activated = Some(menu.selected);
+    }
+    if keys.just_pressed(KeyCode::Space) {
+        activated = Some(actions.len() - 1);
+    }
+    let Some(action) = activated.and_then(|idx| actions.get(idx)) else {
+        return;
+    };
+    if *action == HomeAction::Depart {
+        next_state.set(if career.days_survived >= LAST_DAY {
+            GameState::Retire
+        } else {
+            GameState::Playing
+        });
+    } else if action.perform(&mut player, &mut career, &mut home.0, catalogues) {
+        // Buying in is as good as arriving at an unlocked hideout.
+        if *action == HomeAction::Unlock {
+            end_day(&mut player, &mut career, &mut heat, &mut rng);
+        }
+        if matches!(
+            action,
+            HomeAction::Install(_)
+                | HomeAction::Buy(_)
+                | HomeAction::BuyHull(_)
+                | HomeAction::Fit(_)
+        ) {
+            commands.spawn(AudioBundle {
+                source: game_assets.upgrade.clone(),
+                settings: PlaybackSettings::DESPAWN,
+            });
+        }
+        menu.dirty = true;
+    }
+}
+
+fn rebuild_home_menu(
+    mut commands: Commands,
+    mut menu: ResMut<HomeMenu>,
+    screens: Query<Entity, With<HomeScreen>>,
+    players: Query<&Player>,
+    hideouts: Query<(&HomeInSystem, Option<&SystemName>), With<CurrentSystemRegion>>,
+    career: Res<Career>,
+    upgrades: Res<UpgradeCatalogue>,
+    hulls: Res<HullCatalogue>,
+    save_status: Res<SaveStatus>,
+) {
+    if !menu.dirty && !save_status.is_changed() {
+        return;
+    }
+    let (Ok(player), Ok((home, m_name))) = (players.get_single(), hideouts.get_single()) else {
+        return;
+    };
+    menu.dirty = false;
+    let catalogues = Catalogues {
+        upgrades: &upgrades,
+        hulls: &hulls,
+    };
+    for screen in screens.iter() {
+        commands.entity(screen).despawn_recursive();
+    }
+    // Selling things takes rows away.
+    menu.selected = menu
+        .selected
+        .min(home_actions(player, &home.0, catalogues).len() - 1);
+    spawn_home_screen(
+        &mut commands,
+        player,
+        &career,
+        (m_name.map_or("Hideout", |name| name.0.as_str()), &home.0),
+        catalogues,
+        &save_status,
+        menu.selected,
+    );
+}
+
+#[cfg(test)]
+mod shipyard_tests {
+    use super::*;
+
+    // The catalogues are resources, as they are in the game.
+    fn catalogue_world() -> World {
+        let mut world = World::new();
+        world.insert_resource(
+            UpgradeCatalogue::from_ron(include_str!("../assets/upgrades.ron")).unwrap(),
+        );
+        world
+            .insert_resource(HullCatalogue::from_ron(include_str!("../assets/hulls.ron")).unwrap());
+        world
+    }
+
+    fn catalogues(world: &World) -> Catalogues<'_> {
+        Catalogues {
+            upgrades: world.resource(),
+            hulls: world.resource(),
+        }
+    }
+
+    fn hideout() -> Hideout {
+        Hideout::starting(&mut GameRng::new(2444).world)
+    }
+
+    #[test]
+    fn repairs_stop_when_earnings_run_out() {
+        let world = catalogue_world();
+        let catalogues = catalogues(&world);
+        let mut hideout = hideout();
+        let mut player = Player::new();
+        player.hull = 50.0;
+        let mut career = Career {
+            earnings: 100.0,
+            ..Default::default()
+        };
+        assert!(HomeAction::Repair(50.0).perform(
+            &mut player,
+            &mut career,
+            &mut hideout,
+            catalogues
+        ));
+        assert!((player.hull - 70.0).abs() < 0.001);
+        assert!(career.earnings.abs() < 0.001);
+        assert!(!HomeAction::Repair(50.0).perform(
+            &mut player,
+            &mut career,
+            &mut hideout,
+            catalogues
+        ));
+
+        // Somewhere pricier, the same money goes less far.
+        hideout.repair_multiplier = 2.0;
+        career.earnings = 100.0;
+        assert!(HomeAction::Repair(50.0).perform(
+            &mut player,
+            &mut career,
+            &mut hideout,
+            catalogues
+        ));
+        assert!((player.hull - 80.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn materials_can_be_installed_or_sold() {
+        let world = catalogue_world();
+        let catalogues = catalogues(&world);
+        let mut hideout = hideout();
+        let shields = Upgrade("ShieldStrength".to_string());
+        let mut player = Player::new();
+        player.upgrade_materials = vec![shields.clone(), shields.clone()];
+        player.upgrade_mass = 4.0;
+        let mut career = Career::default();
+
+        let actions = home_actions(&player, &hideout, catalogues);
+        assert_eq!(
+            actions
+                .iter()
+                .filter(|action| **action == HomeAction::Install(shields.clone()))
+                .count(),
+            1
+        );
+        let (upgraded, _) = HomeAction::Install(shields.clone())
+            .preview(&player, &career, &hideout, catalogues)
+            .unwrap();
+        assert!(upgraded.max_shields > player.max_shields);
+        assert_eq!(player.upgrade_materials.len(), 2);
+        assert!(HomeAction::Buy(shields.clone())
+            .preview(&player, &career, &hideout, catalogues)
+            .is_none());
+
+        assert!(HomeAction::SellMaterial(shields.clone()).perform(
+            &mut player,
+            &mut career,
+            &mut hideout,
+            catalogues
+        ));
+        assert_eq!(player.upgrade_materials.len(), 1);
+        assert!((player.upgrade_mass - 2.0).abs() < 0.001);
+        assert!(career.earnings > 0.0);
+    }
+
+    #[test]
+    fn lockers_keep_cargo_between_visits() {
+        let world = catalogue_world();
+        let catalogues = catalogues(&world);
+        let mut hideout = hideout();
+        let engines = Upgrade("EngineUpgrade".to_string());
+        let mut player = Player::new();
+        player.upgrade_materials = vec![engines.clone()];
+        player.upgrade_mass = 3.0;
+        player.exotic_material = 40.0;
+        let mut career = Career::default();
+
+        for action in [HomeAction::StashXm, HomeAction::Stash(engines.clone())] {
+            assert!(action.perform(&mut player, &mut career, &mut hideout, catalogues));
+        }
+        assert_eq!(player.cargo_space_left(), player.max_cargo);
+        assert_eq!(hideout.locker.exotic_material, 40.0);
+
+        player.max_cargo = 20.0;
+        for action in [HomeAction::Take(engines.clone()), HomeAction::TakeXm] {
+            assert!(action.perform(&mut player, &mut career, &mut hideout, catalogues));
+        }
+        assert_eq!(player.upgrade_materials, vec![engines]);
+        assert!((player.exotic_material - 17.0).abs() < 0.001);
+        assert!((hideout.locker.exotic_material - 23.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn locked_hideouts_only_sell_a_way_in() {
+        let world = catalogue_world();
+        let catalogues = catalogues(&world);
+        let mut hideout = Hideout::random(&mut GameRng::new(2444).world);
+        let mut player = Player::new();
+        let mut career = Career {
+            earnings: hideout.price,
+            ..Default::default()
+        };
+        assert_eq!(
+            home_actions(&player, &hideout, catalogues),
+            vec![HomeAction::Unlock, HomeAction::Depart]
+        );
+        assert!(HomeAction::Unlock.perform(&mut player, &mut career, &mut hideout, catalogues));
+        assert!(hideout.unlocked);
+        assert_eq!(career.earnings, 0.0);
+    }
+
+    #[test]
+    fn new_hulls_keep_upgrades_but_need_room_for_the_cargo() {
+        let world = catalogue_world();
+        let catalogues = catalogues(&world);
+        let mut hideout = hideout();
+        let mut player = Player::new();
+        player.apply_upgrade(
+            catalogues
+                .upgrades
+                .get(&Upgrade("HullStrength".to_string()))
+                .unwrap(),
+        );
+        player.salvage_mass = 90.0;
+        player
+            .commodities
+            .insert(Commodity::Ore, player.salvage_mass);
+        let mut career = Career {
+            earnings: 5000.0,
+            ..Default::default()
+        };
+
+        let interceptor = HomeAction::BuyHull("Interceptor".to_string());
+        assert!(interceptor
+            .preview(&player, &career, &hideout, catalogues)
+            .is_none());
+        let hauler = HomeAction::BuyHull("Hauler".to_string());
+        assert!(hauler.perform(&mut player, &mut career, &mut hideout, catalogues));
+        assert_eq!(player.hull_class, "Hauler");
+        assert_eq!(player.salvage_mass, 90.0);
+        let base = catalogues.hulls.get("Hauler").unwrap();
+        assert!(player.max_hull > Player::with_hull(base).max_hull);
+        assert!(career.earnings < 5000.0 - base.price * (1. - HULL_TRADE_IN));
+        assert!(!home_actions(&player, &hideout, catalogues).contains(&hauler));
+    }
+
+    #[test]
+    fn secondary_weapons_need_a_slot_and_ammo() {
+        let world = catalogue_world();
+        let catalogues = catalogues(&world);
+        let mut hideout = hideout();
+        let mut player = Player::with_hull(catalogues.hulls.get("Interceptor").unwrap());
+        player.exotic_material = 25.0;
+        let mut career = Career {
+            earnings: 2000.0,
+            ..Default::default()
+        };
+
+        let fit_missiles = HomeAction::Fit(SecondaryWeapon::Missiles);
+        assert!(fit_missiles.perform(&mut player, &mut career, &mut hideout, catalogues));
+        // Interceptors only have the one slot.
+        assert!(HomeAction::Fit(SecondaryWeapon::Mines)
+            .preview(&player, &career, &hideout, catalogues)
+            .is_none());
+        assert!(!home_actions(&player, &hideout, catalogues).contains(&fit_missiles));
+
+        let craft = HomeAction::CraftAmmo(SecondaryWeapon::Missiles);
+        assert!(craft.perform(&mut player, &mut career, &mut hideout, catalogues));
+        assert!(!craft.perform(&mut player, &mut career, &mut hideout, catalogues));
+        assert!(HomeAction::BuyAmmo(SecondaryWeapon::Missiles).perform(
+            &mut player,
+            &mut career,
+            &mut hideout,
+            catalogues
+        ));
+        assert_eq!(player.secondaries[0].ammo, 8);
+        assert_eq!(player.exotic_material, 5.0);
+    }
+}
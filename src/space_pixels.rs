@@ -19,8 +19,7 @@ pub struct SpacePixel {
 }
 
 impl SpacePixel {
-    pub fn random_jammer() -> Self {
-        let mut rng = rand::thread_rng();
+    pub fn random_jammer(rng: &mut impl Rng) -> Self {
         let lifetime = rng.gen_range(0.0..1.0);
         let vel_range = -JAMMER_VELOCITY_SCALE..JAMMER_VELOCITY_SCALE;
         let velocity = Vec2::new(
@@ -1,19 +1,35 @@
 mod assets;
 mod bullets;
+mod damage;
+mod effects;
+mod fighters;
 mod game_over;
 mod game_state;
+mod heat;
 mod home;
+mod hulls;
 mod indicators;
 mod intro;
 mod jamming;
+mod main_menu;
+mod market;
+mod patrols;
 mod physics;
 mod pickups;
 mod player;
 mod prelude;
+mod projectiles;
+mod rng;
+mod save;
+mod secondary_weapons;
+mod shipyard;
 mod space_pixels;
+#[cfg(test)]
+mod test_harness;
 mod trade_routes;
 mod turrets;
 mod ui;
+mod upgrades;
 
 #[macro_use]
 extern crate lazy_static;
@@ -24,17 +40,17 @@ use bevy::{
     window::WindowResolution,
 };
 use bevy_spine::SpinePlugin;
-use bullets::BulletsPlugin;
+use bullets::{dress_lasers, BulletsPlugin};
 use game_over::GameOverPlugin;
 use home::HomePlugin;
 use indicators::IndicatorsPlugin;
 use intro::IntroPlugin;
 use jamming::{indicate_jamming_on_skeleton, JammingPlugin};
 use physics::PhysicsPlugin;
-use pickups::PickupsPlugin;
+use pickups::{dress_pickups, PickupsPlugin};
 use player::{toggle_player_jet, PlayerPlugin};
 use space_pixels::SpacePixelsPlugin;
-use trade_routes::{toggle_cargo_jet, TradeRoutesPlugin, DAMAGE_ATTACHMENTS, SECTION_DAMAGE_SLOTS};
+use trade_routes::{toggle_cargo_jet, TradeRoutesPlugin, DAMAGE_ATTACHMENTS};
 use ui::GameUiPlugin;
 
 use crate::prelude::*;
@@ -52,11 +68,14 @@ fn main() {
             ..Default::default()
         }))
         .add_plugins((SpinePlugin, GameAssetsPlugin, SpacePixelsPlugin))
+        .add_systems(OnEnter(GameState::MainMenu), skip_main_menu)
         .add_systems(OnEnter(GameState::Playing), stage_cover)
         .add_systems(
             Update,
             (
                 indicate_jamming_on_skeleton,
+                dress_lasers.run_if(resource_exists::<Lasers>()),
+                dress_pickups.run_if(resource_exists::<UpgradeCatalogue>()),
                 animate_cover.run_if(in_state(GameState::Playing)),
             ),
         )
@@ -70,6 +89,11 @@ pub enum Staged {
     Bullet,
 }
 
+// The cover has no careers to pick from.
+fn skip_main_menu(mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::Playing);
+}
+
 fn stage_cover(mut commands: Commands, assets: Res<GameAssets>, skeletons: Res<Skeletons>) {
     commands.spawn((
         Camera2dBundle {
@@ -159,8 +183,6 @@ fn animate_cover(
         (Entity, &mut Transform, &InertiaVolume),
         (Without<Staged>, Or<(With<Pickup>, With<Bullet>)>),
     >,
-    lasers: Res<Lasers>,
-    game_assets: Res<GameAssets>,
 ) {
     let cargo_ship_section = query
         .iter()
@@ -236,9 +258,8 @@ fn animate_cover(
                         transform,
                         my_inertia,
                         &mut commands,
-                        lasers.player_laser_mesh.clone().into(),
-                        lasers.player_laser_material.clone(),
-                        Bullet::Player,
+                        Bullet::player(),
+                        Player::new().laser_damage(),
                     );
                     fire_laser_from_turret(
                         "left_turret",
@@ -246,9 +267,8 @@ fn animate_cover(
                         transform,
                         my_inertia,
                         &mut commands,
-                        lasers.player_laser_mesh.clone().into(),
-                        lasers.player_laser_material.clone(),
-                        Bullet::Player,
+                        Bullet::player(),
+                        Player::new().laser_damage(),
                     );
                     fire_laser_from_turret(
                         "right_turret",
@@ -256,9 +276,8 @@ fn animate_cover(
                         transform,
                         my_inertia,
                         &mut commands,
-                        lasers.player_laser_mesh.clone().into(),
-                        lasers.player_laser_material.clone(),
-                        Bullet::Player,
+                        Bullet::player(),
+                        Player::new().laser_damage(),
                     );
                 }
             }
@@ -293,8 +312,8 @@ fn animate_cover(
                 }
                 let attachment = spine
                     .skeleton
-                    .get_attachment_for_slot_name(SECTION_DAMAGE_SLOTS[5], DAMAGE_ATTACHMENTS[2]);
-                if let Some(mut slot) = spine.skeleton.find_slot_mut(SECTION_DAMAGE_SLOTS[5]) {
+                    .get_attachment_for_slot_name("cargo5_damage", DAMAGE_ATTACHMENTS[2]);
+                if let Some(mut slot) = spine.skeleton.find_slot_mut("cargo5_damage") {
                     unsafe {
                         slot.set_attachment(attachment);
                     }
@@ -314,7 +333,7 @@ fn animate_cover(
                                     .truncate()
                                     * (rand::random::<f32>() * 300.0),
                             &mut commands,
-                            game_assets.salvage.clone(),
+                            Commodity::Scrap,
                             rand::random::<f32>() * 1.0 + 2.0,
                             (rand::random::<f32>() * 20.0 + 10.0) * 2.,
                         );
@@ -0,0 +1,89 @@
+//! A headless `App` running the gameplay plugins, for simulation tests.
+
+use bevy::{
+    ecs::system::CommandQueue, input::InputPlugin, time::TimeUpdateStrategy,
+    transform::TransformPlugin,
+};
+
+use crate::{
+    bullets::BulletsPlugin,
+    effects::EffectsPlugin,
+    fighters::FightersPlugin,
+    jamming::JammingPlugin,
+    patrols::PatrolsPlugin,
+    physics::PhysicsPlugin,
+    pickups::PickupsPlugin,
+    prelude::*,
+    projectiles::ProjectilesPlugin,
+    secondary_weapons::SecondaryWeaponsPlugin,
+    trade_routes::{CargoShipsPlugin, EscortsPlugin},
+};
+
+pub struct Simulation {
+    pub app: App,
+}
+
+impl Simulation {
+    /// Builds the simulation already in `GameState::Playing`, with no windows, audio or skeletons.
+    pub fn new() -> Self {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            HierarchyPlugin,
+            InputPlugin,
+        ))
+        .add_state::<GameState>()
+        .insert_resource(GameRng::new(2444))
+        .insert_resource(
+            UpgradeCatalogue::from_ron(include_str!("../assets/upgrades.ron")).unwrap(),
+        )
+        .add_plugins((
+            EffectsPlugin,
+            PhysicsPlugin::default(),
+            BulletsPlugin,
+            PickupsPlugin,
+            JammingPlugin,
+            CargoShipsPlugin,
+            PatrolsPlugin,
+            FightersPlugin,
+            EscortsPlugin,
+            SecondaryWeaponsPlugin,
+            ProjectilesPlugin,
+        ));
+        // Each update advances time by exactly one physics tick.
+        let tick = app.world.resource::<FixedTime>().period;
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(tick));
+        app.world
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Playing);
+        app.update();
+        Self { app }
+    }
+
+    /// Runs `spawner` with a `Commands` and applies them immediately.
+    pub fn spawn<T>(&mut self, spawner: impl FnOnce(&mut Commands) -> T) -> T {
+        let mut queue = CommandQueue::default();
+        let result = {
+            let mut commands = Commands::new(&mut queue, &self.app.world);
+            spawner(&mut commands)
+        };
+        queue.apply(&mut self.app.world);
+        result
+    }
+
+    /// Advances the simulation by `ticks` physics ticks, one frame each.
+    pub fn step(&mut self, ticks: usize) {
+        for _ in 0..ticks {
+            self.app.update();
+        }
+    }
+
+    pub fn get<T: Component>(&self, entity: Entity) -> Option<&T> {
+        self.app.world.get::<T>(entity)
+    }
+
+    pub fn get_mut<T: Component>(&mut self, entity: Entity) -> Option<Mut<T>> {
+        self.app.world.get_mut::<T>(entity)
+    }
+}
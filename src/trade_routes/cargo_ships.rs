@@ -1,6 +1,44 @@
-use crate::prelude::*;
+use bevy::render::texture::DEFAULT_IMAGE_HANDLE;
 
-#[derive(PartialEq, Debug)]
+use crate::{
+    bullets::ENEMY_LASER, effects::SoundEffect, heat::Heat, patrols::Piracy, physics::PhysicsSet,
+    prelude::*,
+};
+
+use super::{
+    steering::{blend, evade_projectiles, flee, nearest_jammer_exit, seek, steer},
+    SectionDefinition,
+};
+
+pub struct CargoShipsPlugin;
+
+impl Plugin for CargoShipsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                cargo_ship_jet_animation_system,
+                cargo_ship_damage_animation_system,
+                dress_cargo_pods,
+                cargo_ship_defense_system.run_if(in_state(GameState::Playing)),
+                alert_cargo_ships.run_if(resource_exists::<Heat>()),
+            ),
+        )
+        .add_systems(
+            FixedUpdate,
+            (
+                cargo_ship_escape_system
+                    .run_if(in_state(GameState::Playing))
+                    .in_set(PhysicsSet::Forces),
+                cargo_ship_drop_system
+                    .run_if(in_state(GameState::Playing))
+                    .in_set(PhysicsSet::Resolve),
+            ),
+        );
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
 enum CargoShipEscape {
     Passive,
     Jammed,
@@ -8,57 +46,95 @@ enum CargoShipEscape {
     Jumped { progress: f32 },
 }
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct CargoShip {
+    pub class: ShipClass,
+    pub manifest: Manifest,
     pub value_modifier: f32,
     pub aggressed: bool,
-    sections_health: [f32; 8],
-    pub sections_destroyed: [bool; 8],
-    fire_speed: f32,
-    turret_cooldowns: [f32; 2],
+    sections_health: Vec<f32>,
+    pub sections_destroyed: Vec<bool>,
+    // Scales turret cooldowns and hyperdrive spool-up, lower is quicker.
+    reaction_time: f32,
+    turret_cooldowns: Vec<f32>,
+    // Seconds until an EMP wears off and the turret cooldowns start counting down again.
+    turrets_frozen: f32,
     escape_state: CargoShipEscape,
-    jump_time: f32,
 }
 
-const CARGO_SHIP_SECTION_HEALTH: f32 = 75.0;
-
 impl CargoShip {
-    pub fn new(value_modifier: f32) -> Self {
+    pub fn new(class: &ShipClass, manifest: Manifest, value_modifier: f32) -> Self {
         Self {
+            class: class.clone(),
+            manifest,
             value_modifier,
             aggressed: false,
-            sections_health: [CARGO_SHIP_SECTION_HEALTH; 8],
-            sections_destroyed: [false; 8],
-            fire_speed: 1.,
-            turret_cooldowns: [0.0; 2],
+            sections_health: class
+                .sections
+                .iter()
+                .map(|section| section.health)
+                .collect(),
+            sections_destroyed: vec![false; class.sections.len()],
+            reaction_time: 1.0,
+            turret_cooldowns: vec![0.0; class.turrets.len()],
+            turrets_frozen: 0.0,
             escape_state: CargoShipEscape::Passive,
-            jump_time: 3.0,
         }
     }
 
-    pub fn damage_section(&mut self, section: usize, damage: f32) {
+    /// Crews that know pirates are about shoot faster and spool their hyperdrives sooner.
+    pub fn alert(&mut self, alertness: f32) {
+        self.reaction_time = 1. - alertness * 0.5;
+    }
+
+    /// Knocks the turrets out for `duration` seconds, or longer if they're already out.
+    pub fn freeze_turrets(&mut self, duration: f32) {
+        self.turrets_frozen = self.turrets_frozen.max(duration);
+    }
+
+    pub fn turrets_frozen(&self) -> bool {
+        self.turrets_frozen > 0.
+    }
+
+    pub fn jump_time(&self) -> f32 {
+        self.class.jump_time * self.reaction_time
+    }
+
+    pub fn section_count(&self) -> usize {
+        self.class.sections.len()
+    }
+
+    pub fn damage_section(&mut self, section: usize, damage: &Damage) {
         self.aggressed = true;
-        self.sections_health[section] -= damage;
+        self.sections_health[section] -= damage.against(&self.class.section_armour);
     }
 
     pub fn section_must_die(&self, section: usize) -> bool {
         self.sections_health[section] <= 0.0 && !self.sections_destroyed[section]
     }
 
+    pub fn section_health(&self, section: usize) -> f32 {
+        self.sections_health[section]
+    }
+
     pub fn section_alive(&self, section: usize) -> bool {
         !self.sections_destroyed[section]
     }
 
+    /// Every section is gone, only the hull is left drifting.
+    pub fn wrecked(&self) -> bool {
+        self.sections_destroyed.iter().all(|destroyed| *destroyed)
+    }
+
     pub fn section_damaged(&self, section: usize) -> Option<usize> {
-        if self.sections_health[section] <= CARGO_SHIP_SECTION_HEALTH * 0.15
-            && !self.sections_destroyed[section]
-        {
+        let max_health = self.class.sections[section].health;
+        if self.sections_health[section] <= max_health * 0.15 && !self.sections_destroyed[section] {
             Some(2)
-        } else if self.sections_health[section] <= CARGO_SHIP_SECTION_HEALTH * 0.50
+        } else if self.sections_health[section] <= max_health * 0.50
             && !self.sections_destroyed[section]
         {
             Some(1)
-        } else if self.sections_health[section] <= CARGO_SHIP_SECTION_HEALTH * 0.90
+        } else if self.sections_health[section] <= max_health * 0.90
             && !self.sections_destroyed[section]
         {
             Some(0)
@@ -71,73 +147,66 @@ impl CargoShip {
 #[derive(Component, Debug)]
 pub struct CargoSection {
     pub index: usize,
-    pub skeleton_bone: &'static str,
-    pub hit_animation: &'static str,
 }
 
-const CARGO_SHIP_THRUST: f32 = 80000.0;
-const CARGO_SECTION_MASS: f32 = 1000.0;
-const CARGO_SHIP_MASS: f32 = CARGO_SECTION_MASS * 8.0 + 2000.0;
-const SECTION_BONES: [&'static str; 8] = [
-    "cargo0", "cargo1", "cargo2", "cargo3", "cargo4", "cargo5", "cargo6", "cargo7",
-];
-pub const SECTION_DAMAGE_SLOTS: [&'static str; 8] = [
-    "cargo0_damage",
-    "cargo1_damage",
-    "cargo2_damage",
-    "cargo3_damage",
-    "cargo4_damage",
-    "cargo5_damage",
-    "cargo6_damage",
-    "cargo7_damage",
-];
+const CARGO_SHIP_TURN_RATE: f32 = 0.4;
 pub const DAMAGE_ATTACHMENTS: [&'static str; 3] = ["Damage0", "Damage1", "Damage2"];
-pub const SECTION_HIT_ANIMATIONS: [&'static str; 8] = [
-    "jiggle0", "jiggle1", "jiggle2", "jiggle3", "jiggle4", "jiggle5", "jiggle6", "jiggle7",
-];
-const SECTION_OFFSETS: [(f32, f32); 8] = [
-    (-144., -16.),
-    (-80., -16.),
-    (-16., -16.),
-    (48., -16.),
-    (-144., 16.),
-    (-80., 16.),
-    (-16., 16.),
-    (48., 16.),
-];
+const CARGO_POD_SIZE: Vec2 = Vec2::new(56., 28.);
 
 impl CargoSection {
-    pub fn bundle(index: usize) -> (Transform, GlobalTransform, Self, InertiaVolume) {
+    pub fn bundle(
+        index: usize,
+        definition: &SectionDefinition,
+    ) -> (
+        Transform,
+        GlobalTransform,
+        Self,
+        InertiaVolume,
+        Solid,
+        CollisionLayers,
+    ) {
         (
-            Transform::from_xyz(SECTION_OFFSETS[index].0, SECTION_OFFSETS[index].1, 0.),
+            Transform::from_xyz(definition.offset.0, definition.offset.1, 0.),
             GlobalTransform::default(),
-            CargoSection {
-                index,
-                skeleton_bone: SECTION_BONES[index],
-                hit_animation: SECTION_HIT_ANIMATIONS[index],
-            },
+            CargoSection { index },
             InertiaVolume::new(1.0, 32.0),
+            Solid {
+                restitution: 0.3,
+                takes_ram_damage: true,
+            },
+            CollisionLayers::new(
+                CollisionLayers::CARGO_SHIP,
+                CollisionLayers::PLAYER
+                    | CollisionLayers::CARGO_SHIP
+                    | CollisionLayers::PLAYER_BULLET,
+            ),
         )
     }
 }
 
+/// Spawns `count` ships flying in formation from the edge of the arena, returning the ships
+/// and where they start.
 pub fn spawn_cargo_ships(
-    mut commands: Commands,
-    game_assets: Res<GameAssets>,
-    skeletons: Res<Skeletons>,
-    count: usize,
-) {
-    let angle = rand::thread_rng().gen_range(0.0..PI * 2.0);
+    commands: &mut Commands,
+    game_assets: &GameAssets,
+    skeletons: &Skeletons,
+    rng: &mut impl Rng,
+    classes: &[&ShipClass],
+    manifest: Manifest,
+    value_modifier: f32,
+) -> Vec<(Entity, Transform)> {
+    let angle = rng.gen_range(0.0..PI * 2.0);
     let direction = Vec2::new(f32::cos(angle), f32::sin(angle));
     let transform = Transform::from_xyz(
         direction.x * ARENA_SIZE * 2.,
         direction.y * ARENA_SIZE * 2.,
         0.,
     );
-    let mut inertia = InertiaVolume::new(CARGO_SHIP_MASS, 0.0);
-    inertia.velocity = -direction * 20.0;
-    inertia.set_rotation(-angle);
-    for idx in 0..count {
+    let mut cargo_ships = Vec::with_capacity(classes.len());
+    for (idx, class) in classes.iter().enumerate() {
+        let mut inertia = InertiaVolume::new(class.mass(), 0.0);
+        inertia.velocity = -direction * 20.0;
+        inertia.set_rotation(-angle);
         let mut my_transform = transform.clone();
         if idx == 1 {
             my_transform.translation.x += direction.y * 300.0;
@@ -147,32 +216,103 @@ pub fn spawn_cargo_ships(
             my_transform.translation.y += direction.x * 300.0;
         }
         println!("Spawning cargo ship at {:?}", my_transform.translation);
-        let (indicator, indicator_text) =
-            create_indicator_with_text(&mut commands, &game_assets, true);
-        commands
-            .spawn((
-                SpineBundle {
-                    transform: my_transform,
-                    skeleton: skeletons.cargo_ship.clone(),
+        let cargo_ship = spawn_cargo_ship(
+            commands,
+            my_transform,
+            inertia,
+            CargoShip::new(class, manifest, value_modifier),
+        );
+        dress_cargo_ship(
+            commands,
+            cargo_ship,
+            my_transform,
+            class,
+            game_assets,
+            skeletons,
+        );
+        cargo_ships.push((cargo_ship, my_transform));
+    }
+    cargo_ships
+}
+
+/// Spawns a cargo ship and its surviving sections, without a skeleton or indicator.
+pub fn spawn_cargo_ship(
+    commands: &mut Commands,
+    transform: Transform,
+    inertia: InertiaVolume,
+    cargo_ship: CargoShip,
+) -> Entity {
+    let sections = cargo_ship
+        .class
+        .sections
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !cargo_ship.sections_destroyed[*index])
+        .map(|(index, definition)| CargoSection::bundle(index, definition))
+        .collect::<Vec<_>>();
+    commands
+        .spawn((
+            SpatialBundle::from_transform(transform),
+            inertia,
+            cargo_ship,
+            Regional,
+            Jammable,
+        ))
+        .with_children(|parent| {
+            for section in sections {
+                parent.spawn(section);
+            }
+        })
+        .id()
+}
+
+pub fn dress_cargo_ship(
+    commands: &mut Commands,
+    cargo_ship: Entity,
+    transform: Transform,
+    class: &ShipClass,
+    game_assets: &GameAssets,
+    skeletons: &Skeletons,
+) {
+    let (indicator, indicator_text) = create_indicator_with_text(commands, game_assets, true);
+    commands.entity(cargo_ship).insert((
+        SpineBundle {
+            transform,
+            skeleton: skeletons
+                .get(&class.skeleton)
+                .unwrap_or(&skeletons.cargo_ship)
+                .clone(),
+            ..Default::default()
+        },
+        DistantIndicator::new_local(indicator, indicator_text),
+    ));
+}
+
+/// Sections without a bone in the ship's skeleton get a plain cargo pod instead.
+fn dress_cargo_pods(
+    mut commands: Commands,
+    sections: Query<(Entity, &CargoSection, &Parent), Added<CargoSection>>,
+    cargo_ships: Query<&CargoShip>,
+) {
+    for (entity, section, parent) in sections.iter() {
+        let Ok(cargo_ship) = cargo_ships.get(parent.get()) else {
+            continue;
+        };
+        if cargo_ship.class.sections[section.index].bone.is_some() {
+            continue;
+        }
+        commands.add(InsertSafe {
+            entity,
+            bundle: (
+                Sprite {
+                    color: Color::rgb(0.55, 0.45, 0.3),
+                    custom_size: Some(CARGO_POD_SIZE),
                     ..Default::default()
                 },
-                inertia.clone(),
-                DistantIndicator::new_local(indicator, indicator_text),
-                CargoShip::new(count as f32 * 0.5 + 0.5),
-                Regional,
-                Jammable,
-            ))
-            .with_children(|parent| {
-                // Spawn all 8 cargo sections.
-                parent.spawn((CargoSection::bundle(0),));
-                parent.spawn((CargoSection::bundle(1),));
-                parent.spawn((CargoSection::bundle(2),));
-                parent.spawn((CargoSection::bundle(3),));
-                parent.spawn((CargoSection::bundle(4),));
-                parent.spawn((CargoSection::bundle(5),));
-                parent.spawn((CargoSection::bundle(6),));
-                parent.spawn((CargoSection::bundle(7),));
-            });
+                DEFAULT_IMAGE_HANDLE.typed::<Image>(),
+                VisibilityBundle::default(),
+            ),
+        });
     }
 }
 
@@ -202,77 +342,85 @@ pub fn cargo_ship_jet_animation_system(mut players: Query<(&CargoShip, &mut Spin
 
 pub fn cargo_ship_drop_system(
     mut commands: Commands,
-    game_assets: Res<GameAssets>,
-    mut cargo_ships: Query<(Entity, &mut CargoShip, &mut InertiaVolume, &mut Spine)>,
+    mut cargo_ships: Query<(Entity, &mut CargoShip, &mut InertiaVolume)>,
     cargo_sections: Query<(Entity, &CargoSection, &Parent, &GlobalTransform)>,
+    upgrades: Res<UpgradeCatalogue>,
+    mut rng: ResMut<GameRng>,
+    mut sound_effects: EventWriter<SoundEffect>,
+    mut piracy: EventWriter<Piracy>,
 ) {
-    for (ship_entity, mut cargo_ship, mut ship_inertia, mut cargo_skeleton) in
-        cargo_ships.iter_mut()
-    {
-        for section_idx in 0..8 {
+    let rng = &mut rng.loot;
+    for (ship_entity, mut cargo_ship, mut ship_inertia) in cargo_ships.iter_mut() {
+        for section_idx in 0..cargo_ship.section_count() {
             if cargo_ship.section_must_die(section_idx) {
                 cargo_ship.sections_destroyed[section_idx] = true;
-                commands.spawn(AudioBundle {
-                    source: game_assets.cargo_ship_section_destroyed.clone(),
-                    settings: PlaybackSettings::DESPAWN,
-                });
+                sound_effects.send(SoundEffect::CargoShipSectionDestroyed);
+                piracy.send(Piracy { severity: 1.0 });
                 // Pinata!
-                if let Some((ship_section, section, _parent, transform)) = cargo_sections
+                if let Some((ship_section, _section, _parent, transform)) = cargo_sections
                     .iter()
                     .find(|(_ship_section, section, parent, _)| {
                         parent.get() == ship_entity && section.index == section_idx
                     })
                 {
-                    for _ in 0..10 {
-                        spawn_salvage(
+                    for drop in cargo_ship.manifest.loot_table().roll(rng) {
+                        spawn_loot(
                             transform.translation().x,
                             transform.translation().y,
                             ship_inertia.velocity
-                                + Quat::from_rotation_z(rand::random::<f32>() * PI * 2.)
+                                + Quat::from_rotation_z(rng.gen::<f32>() * PI * 2.)
                                     .mul_vec3(Vec3::X)
                                     .truncate()
-                                    * (rand::random::<f32>() * 100.0),
+                                    * (rng.gen::<f32>() * 100.0),
                             &mut commands,
-                            game_assets.salvage.clone(),
-                            rand::random::<f32>() * 1.0 + 2.0,
-                            (rand::random::<f32>() * 20.0 + 10.0) * cargo_ship.value_modifier,
+                            rng,
+                            drop,
+                            cargo_ship.value_modifier,
+                            &upgrades,
                         );
                     }
-                    spawn_upgrade(
-                        transform.translation().x,
-                        transform.translation().y,
-                        ship_inertia.velocity
-                            + Quat::from_rotation_z(rand::random::<f32>() * PI * 2.)
-                                .mul_vec3(Vec3::X)
-                                .truncate()
-                                * (rand::random::<f32>() * 50.0),
-                        &mut commands,
-                        game_assets.upgrades.clone(),
-                        Upgrade::random(),
-                    );
-                    ship_inertia.mass -= CARGO_SECTION_MASS;
+                    ship_inertia.mass -= cargo_ship.class.section_mass;
                     commands.entity(ship_section).despawn();
-                    if let Some(mut section_bone) =
-                        cargo_skeleton.skeleton.find_bone_mut(section.skeleton_bone)
-                    {
-                        // Make the section disappear!
-                        section_bone.set_scale_x(0.);
-                    }
                 }
-            } else if cargo_ship.section_alive(section_idx) {
+            }
+        }
+    }
+}
+
+fn alert_cargo_ships(heat: Res<Heat>, mut cargo_ships: Query<&mut CargoShip, Added<CargoShip>>) {
+    for mut cargo_ship in cargo_ships.iter_mut() {
+        cargo_ship.alert(heat.alertness());
+    }
+}
+
+pub fn cargo_ship_damage_animation_system(mut cargo_ships: Query<(&CargoShip, &mut Spine)>) {
+    for (cargo_ship, mut cargo_skeleton) in cargo_ships.iter_mut() {
+        for bone in cargo_ship.class.hidden_bones.iter() {
+            if let Some(mut hidden_bone) = cargo_skeleton.skeleton.find_bone_mut(bone) {
+                hidden_bone.set_scale_x(0.);
+            }
+        }
+        for (section_idx, section) in cargo_ship.class.sections.iter().enumerate() {
+            if !cargo_ship.section_alive(section_idx) {
+                if let Some(mut section_bone) = section
+                    .bone
+                    .as_ref()
+                    .and_then(|bone| cargo_skeleton.skeleton.find_bone_mut(bone))
+                {
+                    // Make the section disappear!
+                    section_bone.set_scale_x(0.);
+                }
+            } else if let Some(damage_slot) = section.damage_slot.as_ref() {
                 let attachment =
                     cargo_ship
                         .section_damaged(section_idx)
                         .and_then(|damage_amount| {
                             cargo_skeleton.skeleton.get_attachment_for_slot_name(
-                                SECTION_DAMAGE_SLOTS[section_idx],
+                                damage_slot,
                                 DAMAGE_ATTACHMENTS[damage_amount],
                             )
                         });
-                if let Some(mut slot) = cargo_skeleton
-                    .skeleton
-                    .find_slot_mut(SECTION_DAMAGE_SLOTS[section_idx])
-                {
+                if let Some(mut slot) = cargo_skeleton.skeleton.find_slot_mut(damage_slot) {
                     unsafe {
                         slot.set_attachment(attachment);
                     }
@@ -283,26 +431,65 @@ pub fn cargo_ship_drop_system(
 }
 
 const CARGO_SHIP_LASER_SPEED: f32 = 500.0;
-const CARGO_SHIP_LASER_DISTANCE_SQ: f32 = 300.0 * 300.0;
+
+/// Where an escaping cargo ship wants to go: out of any jamming field and away from the
+/// player, dodging fire on the way.
+fn escape_heading(
+    position: Vec2,
+    inertia: &InertiaVolume,
+    player: Option<Vec2>,
+    jammers: &Query<(&Transform, &Jammer)>,
+    bullets: &Query<(&Transform, &InertiaVolume, &Bullet), Without<CargoShip>>,
+) -> Vec2 {
+    let evade = evade_projectiles(
+        position,
+        inertia.velocity,
+        bullets
+            .iter()
+            .filter(|(_, _, bullet)| bullet.owner == Owner::Player)
+            .map(|(transform, inertia, _)| (transform.translation.truncate(), inertia.velocity)),
+    );
+    let exit = nearest_jammer_exit(
+        position,
+        jammers
+            .iter()
+            .map(|(transform, jammer)| (transform.translation.truncate(), jammer.radius)),
+    )
+    .map(|exit| seek(position, exit));
+    let away = player.map(|player| flee(position, player));
+    let ahead = Vec2::from_angle(inertia.rotation());
+    blend([(evade, 0.5), (exit, 1.0), (away, 1.0), (Some(ahead), 1.0)])
+}
 
 pub fn cargo_ship_escape_system(
-    time: Res<Time>,
+    fixed_time: Res<FixedTime>,
     mut cargo_ships: Query<(
         Entity,
         &mut CargoShip,
+        &Transform,
         &mut InertiaVolume,
-        &DistantIndicator,
+        Option<&DistantIndicator>,
         Option<&Jammed>,
     )>,
+    players: Query<&Transform, With<Player>>,
+    jammers: Query<(&Transform, &Jammer)>,
+    bullets: Query<(&Transform, &InertiaVolume, &Bullet), Without<CargoShip>>,
     mut commands: Commands,
-    game_assets: Res<GameAssets>,
+    mut sound_effects: EventWriter<SoundEffect>,
+    mut piracy: EventWriter<Piracy>,
 ) {
-    let dt = time.delta_seconds();
+    let dt = fixed_time.period.as_secs_f32();
     let someone_aggressed = cargo_ships
         .iter_mut()
-        .any(|(_, cargo_ship, _, _, _)| cargo_ship.aggressed);
-    for (cargo_entity, mut cargo_ship, mut inertia, indicators, m_jammed) in cargo_ships.iter_mut()
+        .any(|(_, cargo_ship, ..)| cargo_ship.aggressed);
+    let player = players
+        .get_single()
+        .ok()
+        .map(|transform| transform.translation.truncate());
+    for (cargo_entity, mut cargo_ship, transform, mut inertia, m_indicators, m_jammed) in
+        cargo_ships.iter_mut()
     {
+        let position = transform.translation.truncate();
         if cargo_ship.aggressed && cargo_ship.escape_state == CargoShipEscape::Passive {
             cargo_ship.escape_state = if m_jammed.is_some() {
                 CargoShipEscape::Jammed
@@ -317,12 +504,18 @@ pub fn cargo_ship_escape_system(
                 if m_jammed.is_some() {
                     cargo_ship.escape_state = CargoShipEscape::Jammed;
                 } else {
-                    inertia.apply_thrust_force(CARGO_SHIP_THRUST, dt);
-                    if progress > cargo_ship.jump_time {
-                        commands.spawn(AudioBundle {
-                            source: game_assets.cargo_ship_hyperdrive.clone(),
-                            settings: PlaybackSettings::DESPAWN,
-                        });
+                    let heading = escape_heading(position, &inertia, player, &jammers, &bullets);
+                    steer(
+                        &mut inertia,
+                        heading,
+                        CARGO_SHIP_TURN_RATE,
+                        cargo_ship.class.thrust,
+                        dt,
+                    );
+                    if progress > cargo_ship.jump_time() {
+                        sound_effects.send(SoundEffect::CargoShipHyperdrive);
+                        // It got away, and it will report you.
+                        piracy.send(Piracy { severity: 3.0 });
                         cargo_ship.escape_state = CargoShipEscape::Jumped { progress: 0.0 };
                     } else {
                         cargo_ship.escape_state = CargoShipEscape::Jumping {
@@ -332,11 +525,14 @@ pub fn cargo_ship_escape_system(
                 }
             }
             CargoShipEscape::Jumped { progress } => {
+                inertia.rotation_velocity = 0.;
                 inertia.set_forward_speed(HYPERDRIVE_SPEED * 2.0);
                 if progress > 1. {
                     commands.entity(cargo_entity).despawn_recursive();
-                    commands.entity(indicators.get_indicator()).despawn();
-                    commands.entity(indicators.get_indicator_text()).despawn();
+                    if let Some(indicators) = m_indicators {
+                        commands.entity(indicators.get_indicator()).despawn();
+                        commands.entity(indicators.get_indicator_text()).despawn();
+                    }
                 } else {
                     cargo_ship.escape_state = CargoShipEscape::Jumped {
                         progress: progress + dt,
@@ -347,7 +543,15 @@ pub fn cargo_ship_escape_system(
                 if m_jammed.is_none() {
                     cargo_ship.escape_state = CargoShipEscape::Jumping { progress: 0.0 };
                 } else {
-                    inertia.apply_thrust_force(CARGO_SHIP_THRUST, dt);
+                    // Break out of the jamming field, so the hyperdrive can spool up.
+                    let heading = escape_heading(position, &inertia, player, &jammers, &bullets);
+                    steer(
+                        &mut inertia,
+                        heading,
+                        CARGO_SHIP_TURN_RATE,
+                        cargo_ship.class.thrust,
+                        dt,
+                    );
                 }
             }
             CargoShipEscape::Passive => {
@@ -362,8 +566,7 @@ pub fn cargo_ship_defense_system(
     players: Query<(&Player, &Transform, &InertiaVolume)>,
     mut cargo_ships: Query<(&mut CargoShip, &Transform, &InertiaVolume, &mut Spine)>,
     mut commands: Commands,
-    lasers: Res<Lasers>,
-    game_assets: Res<GameAssets>,
+    mut sound_effects: EventWriter<SoundEffect>,
 ) {
     if players.is_empty() {
         return;
@@ -371,18 +574,29 @@ pub fn cargo_ship_defense_system(
     let player_position = players.single().1.translation;
     let player_velocity = players.single().2.velocity;
     for (mut cargo_ship, location, inertia, mut spine) in cargo_ships.iter_mut() {
+        if cargo_ship.turrets_frozen() {
+            cargo_ship.turrets_frozen -= time.delta_seconds();
+            continue;
+        }
         if !cargo_ship.aggressed {
             continue;
         }
         let relative_velocity = player_velocity - inertia.velocity;
-        for (turret_idx, turret_name) in ["forward_turret", "rear_turret"].iter().enumerate() {
-            cargo_ship.turret_cooldowns[turret_idx] -= time.delta_seconds();
+        let CargoShip {
+            class,
+            turret_cooldowns,
+            reaction_time,
+            ..
+        } = &mut *cargo_ship;
+        for (turret_idx, turret) in class.turrets.iter().enumerate() {
+            let turret_name = turret.bone.as_str();
+            turret_cooldowns[turret_idx] -= time.delta_seconds();
             let local_turret_location = get_turret_location(&spine, turret_name);
             let turret_location = location
                 .transform_point(local_turret_location.extend(0.0))
                 .truncate();
             let delta = player_position.truncate() - turret_location;
-            if delta.length_squared() > CARGO_SHIP_LASER_DISTANCE_SQ {
+            if delta.length_squared() > turret.range * turret.range {
                 // Too far away to shoot
                 continue;
             }
@@ -399,21 +613,17 @@ pub fn cargo_ship_defense_system(
                     target_location,
                     inertia,
                 );
-                if cargo_ship.turret_cooldowns[turret_idx] <= 0.0 {
-                    cargo_ship.turret_cooldowns[turret_idx] = cargo_ship.fire_speed;
-                    commands.spawn(AudioBundle {
-                        source: game_assets.cargo_ship_laser.clone(),
-                        settings: PlaybackSettings::DESPAWN,
-                    });
+                if turret_cooldowns[turret_idx] <= 0.0 {
+                    turret_cooldowns[turret_idx] = turret.cooldown * *reaction_time;
+                    sound_effects.send(SoundEffect::CargoShipLaser);
                     fire_laser_from_turret(
                         turret_name,
                         &spine,
                         location,
                         inertia,
                         &mut commands,
-                        lasers.cargo_ship_laser_mesh.clone().into(),
-                        lasers.cargo_ship_laser_material.clone(),
-                        Bullet::Enemy,
+                        Bullet::enemy(),
+                        ENEMY_LASER,
                     );
                 }
             } else {
@@ -429,3 +639,43 @@ pub fn cargo_ship_defense_system(
         }
     }
 }
+
+#[cfg(test)]
+mod cargo_ships_tests {
+    use super::*;
+    use crate::{jamming::spawn_jammer, test_harness::Simulation};
+
+    fn aggressed_cargo_ship(simulation: &mut Simulation) -> Entity {
+        let cargo_ship = simulation.spawn(|commands| {
+            spawn_cargo_ship(
+                commands,
+                Transform::default(),
+                InertiaVolume::new(ShipClass::freighter().mass(), 0.0),
+                CargoShip::new(&ShipClass::freighter(), Manifest::Ore, 1.0),
+            )
+        });
+        simulation
+            .get_mut::<CargoShip>(cargo_ship)
+            .unwrap()
+            .aggressed = true;
+        cargo_ship
+    }
+
+    #[test]
+    fn jammed_cargo_ship_never_jumps() {
+        let mut simulation = Simulation::new();
+        let cargo_ship = aggressed_cargo_ship(&mut simulation);
+        simulation.spawn(|commands| spawn_jammer(commands, Vec3::ZERO, Vec2::ZERO, 1000.0));
+        simulation.step(600);
+        let cargo_ship = simulation.get::<CargoShip>(cargo_ship).unwrap();
+        assert_eq!(cargo_ship.escape_state, CargoShipEscape::Jammed);
+    }
+
+    #[test]
+    fn unjammed_cargo_ship_escapes() {
+        let mut simulation = Simulation::new();
+        let cargo_ship = aggressed_cargo_ship(&mut simulation);
+        simulation.step(600);
+        assert!(simulation.get::<CargoShip>(cargo_ship).is_none());
+    }
+}
@@ -0,0 +1,394 @@
+use crate::{heat::Heat, home::HomeInSystem, prelude::*};
+
+use super::{
+    escort_count, spawn_cargo_ships, spawn_escorts, CargoShipsInSystem, CurrentSystemRegion, Lanes,
+};
+
+pub struct ConvoysPlugin;
+
+impl Plugin for ConvoysPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Convoys>()
+            .add_systems(
+                FixedUpdate,
+                (maintain_convoys, advance_convoys)
+                    .chain()
+                    .run_if(in_state(GameState::Playing).or_else(in_state(GameState::Hyperdrive))),
+            )
+            .add_systems(
+                Update,
+                spawn_docked_convoys
+                    .run_if(in_state(GameState::Playing).or_else(in_state(GameState::Hyperdrive))),
+            )
+            .add_systems(OnEnter(GameState::Hyperdrive), muster_departing_convoys);
+    }
+}
+
+// In AU per second, same as the distances shown on system indicators.
+const CONVOY_SPEED: f32 = 10.0;
+const MIN_DOCKED_TIME: f32 = 20.0;
+const MAX_DOCKED_TIME: f32 = 45.0;
+const MAX_ESCORT_LEVEL: u32 = 2;
+
+/// Cargo traffic across the whole galaxy, simulated even where the player isn't. Not saved, so
+/// a loaded career starts with fresh convoys.
+#[derive(Resource, Default)]
+pub struct Convoys {
+    next_id: u32,
+    pub convoys: Vec<Convoy>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Convoy {
+    pub id: u32,
+    /// The class of each ship in the convoy.
+    pub ships: Vec<String>,
+    /// What every ship in the convoy is hauling.
+    pub manifest: Manifest,
+    /// Total value of the cargo, shared between the ships.
+    pub manifest_value: f32,
+    /// How heavily guarded the convoy is, from 0 to `MAX_ESCORT_LEVEL`. Richer convoys get
+    /// more escorts on top of this.
+    pub escort_level: u32,
+    pub leg: ConvoyLeg,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConvoyLeg {
+    Docked {
+        system: Entity,
+        departs_in: f32,
+    },
+    InTransit {
+        from: Entity,
+        to: Entity,
+        arrives_in: f32,
+    },
+}
+
+/// Marks a spawned cargo ship as belonging to a convoy, by id.
+#[derive(Component, Debug)]
+pub struct ConvoyShip(pub u32);
+
+impl Convoy {
+    pub fn value_modifier(&self) -> f32 {
+        self.manifest_value / self.ships.len().max(1) as f32
+    }
+
+    pub fn docked_at(&self) -> Option<Entity> {
+        match self.leg {
+            ConvoyLeg::Docked { system, .. } => Some(system),
+            _ => None,
+        }
+    }
+}
+
+impl Convoys {
+    /// Seconds until the next convoy arrives at `system`, if any are on their way.
+    pub fn arrival_time(&self, system: Entity) -> Option<f32> {
+        self.convoys
+            .iter()
+            .filter_map(|convoy| match convoy.leg {
+                ConvoyLeg::InTransit { to, arrives_in, .. } if to == system => Some(arrives_in),
+                _ => None,
+            })
+            .min_by(|a, b| a.total_cmp(b))
+    }
+
+    pub fn docked_count(&self, system: Entity) -> usize {
+        self.convoys
+            .iter()
+            .filter(|convoy| convoy.docked_at() == Some(system))
+            .count()
+    }
+}
+
+fn spawn_convoy_ships(
+    commands: &mut Commands,
+    game_assets: &GameAssets,
+    skeletons: &Skeletons,
+    ship_classes: &ShipClassCatalogue,
+    rng: &mut impl Rng,
+    convoy: &Convoy,
+) {
+    let classes = convoy
+        .ships
+        .iter()
+        .filter_map(|class| ship_classes.get(class))
+        .collect::<Vec<_>>();
+    let cargo_ships = spawn_cargo_ships(
+        commands,
+        game_assets,
+        skeletons,
+        rng,
+        &classes,
+        convoy.manifest,
+        convoy.value_modifier(),
+    );
+    for (cargo_ship, _) in cargo_ships.iter() {
+        commands.entity(*cargo_ship).insert(ConvoyShip(convoy.id));
+    }
+    spawn_escorts(
+        commands,
+        &cargo_ships,
+        escort_count(convoy.escort_level, convoy.manifest_value),
+    );
+}
+
+/// Drops convoys from a galaxy that no longer exists, and sends out new ones from the
+/// cargo ship systems to keep one convoy per system.
+fn maintain_convoys(
+    mut convoys: ResMut<Convoys>,
+    systems: Query<(Entity, Option<&CargoShipsInSystem>), With<Lanes>>,
+    current_system: Query<Entity, With<CurrentSystemRegion>>,
+    mut rng: ResMut<GameRng>,
+    heat: Res<Heat>,
+    ship_classes: Res<ShipClassCatalogue>,
+) {
+    convoys.convoys.retain(|convoy| match convoy.leg {
+        ConvoyLeg::Docked { system, .. } => systems.contains(system),
+        ConvoyLeg::InTransit { from, to, .. } => systems.contains(from) && systems.contains(to),
+    });
+    let ports = systems
+        .iter()
+        .filter(|(entity, m_cargo_ships)| {
+            m_cargo_ships.is_some() && !current_system.contains(*entity)
+        })
+        .map(|(entity, _)| entity)
+        .collect::<Vec<_>>();
+    let target = systems
+        .iter()
+        .filter(|(_, m_cargo_ships)| m_cargo_ships.is_some())
+        .count();
+    let rng = &mut rng.traffic;
+    // Wanted pirates mean better guarded convoys.
+    let extra_escorts = (heat.alertness() * MAX_ESCORT_LEVEL as f32).round() as u32;
+    while convoys.convoys.len() < target && !ports.is_empty() {
+        let count = if rng.gen_bool(0.25) {
+            2
+        } else if rng.gen_bool(0.33) {
+            3
+        } else {
+            1
+        };
+        let ships = (0..count)
+            .filter_map(|_| ship_classes.random(rng))
+            .map(|class| class.id.clone())
+            .collect::<Vec<_>>();
+        let id = convoys.next_id;
+        convoys.next_id += 1;
+        convoys.convoys.push(Convoy {
+            id,
+            ships,
+            manifest: Manifest::random(rng),
+            manifest_value: (0..count).map(|_| rng.gen_range(0.75..1.75)).sum(),
+            escort_level: (rng.gen_range(0..=MAX_ESCORT_LEVEL) + extra_escorts)
+                .min(MAX_ESCORT_LEVEL),
+            leg: ConvoyLeg::Docked {
+                system: ports[rng.gen_range(0..ports.len())],
+                departs_in: rng.gen_range(MIN_DOCKED_TIME..MAX_DOCKED_TIME),
+            },
+        });
+    }
+}
+
+fn advance_convoys(
+    fixed_time: Res<FixedTime>,
+    game_state: Res<State<GameState>>,
+    mut convoys: ResMut<Convoys>,
+    systems: Query<(&SystemLocation, &Lanes, Option<&HomeInSystem>)>,
+    current_system: Query<Entity, With<CurrentSystemRegion>>,
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    skeletons: Res<Skeletons>,
+    ship_classes: Res<ShipClassCatalogue>,
+    mut rng: ResMut<GameRng>,
+) {
+    let dt = fixed_time.period.as_secs_f32();
+    let current_system = current_system.get_single().ok();
+    let rng = &mut rng.traffic;
+    for convoy in convoys.convoys.iter_mut() {
+        match convoy.leg {
+            // The player is watching these ships, so they stay until the player leaves.
+            ConvoyLeg::Docked { system, .. } if Some(system) == current_system => {}
+            ConvoyLeg::Docked { system, departs_in } if departs_in > dt => {
+                convoy.leg = ConvoyLeg::Docked {
+                    system,
+                    departs_in: departs_in - dt,
+                };
+            }
+            ConvoyLeg::Docked { system, .. } => {
+                let Ok((location, lanes, _)) = systems.get(system) else {
+                    continue;
+                };
+                // Convoys steer clear of pirate hideouts, unless there's no other way.
+                let safe_lanes = lanes
+                    .0
+                    .iter()
+                    .filter(|neighbour| matches!(systems.get(**neighbour), Ok((_, _, None))))
+                    .copied()
+                    .collect::<Vec<_>>();
+                let options = if safe_lanes.is_empty() {
+                    &lanes.0
+                } else {
+                    &safe_lanes
+                };
+                if options.is_empty() {
+                    continue;
+                }
+                let to = options[rng.gen_range(0..options.len())];
+                if let Ok((destination, ..)) = systems.get(to) {
+                    convoy.leg = ConvoyLeg::InTransit {
+                        from: system,
+                        to,
+                        arrives_in: location.location.distance(destination.location) / CONVOY_SPEED,
+                    };
+                }
+            }
+            ConvoyLeg::InTransit {
+                from,
+                to,
+                arrives_in,
+            } if arrives_in > dt => {
+                convoy.leg = ConvoyLeg::InTransit {
+                    from,
+                    to,
+                    arrives_in: arrives_in - dt,
+                };
+            }
+            ConvoyLeg::InTransit { to, .. } => {
+                convoy.leg = ConvoyLeg::Docked {
+                    system: to,
+                    departs_in: rng.gen_range(MIN_DOCKED_TIME..MAX_DOCKED_TIME),
+                };
+                if Some(to) == current_system && *game_state == GameState::Playing {
+                    spawn_convoy_ships(
+                        &mut commands,
+                        &game_assets,
+                        &skeletons,
+                        &ship_classes,
+                        rng,
+                        convoy,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Jumping into a system brings in the convoys that are actually docked there.
+fn spawn_docked_convoys(
+    arrivals: Query<Entity, (Added<CurrentSystemRegion>, Without<HomeInSystem>)>,
+    convoys: Res<Convoys>,
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    skeletons: Res<Skeletons>,
+    ship_classes: Res<ShipClassCatalogue>,
+    mut rng: ResMut<GameRng>,
+) {
+    for system in arrivals.iter() {
+        for convoy in convoys.convoys.iter() {
+            if convoy.docked_at() == Some(system) {
+                spawn_convoy_ships(
+                    &mut commands,
+                    &game_assets,
+                    &skeletons,
+                    &ship_classes,
+                    &mut rng.traffic,
+                    convoy,
+                );
+            }
+        }
+    }
+}
+
+/// When the player leaves, convoys keep only the ships that are still flying, and the cargo
+/// still aboard them. Convoys with nothing left are gone for good.
+fn muster_departing_convoys(
+    mut convoys: ResMut<Convoys>,
+    current_system: Query<Entity, With<CurrentSystemRegion>>,
+    convoy_ships: Query<(&ConvoyShip, &CargoShip)>,
+) {
+    let Ok(current_system) = current_system.get_single() else {
+        return;
+    };
+    convoys.convoys.retain_mut(|convoy| {
+        if convoy.docked_at() != Some(current_system) {
+            return true;
+        }
+        let value_modifier = convoy.value_modifier();
+        let survivors = convoy_ships
+            .iter()
+            .filter(|(convoy_ship, cargo_ship)| convoy_ship.0 == convoy.id && !cargo_ship.wrecked())
+            .map(|(_, cargo_ship)| cargo_ship)
+            .collect::<Vec<_>>();
+        convoy.ships = survivors
+            .iter()
+            .map(|cargo_ship| cargo_ship.class.id.clone())
+            .collect();
+        convoy.manifest_value = survivors
+            .iter()
+            .map(|cargo_ship| {
+                let sections_left = (0..cargo_ship.section_count())
+                    .filter(|section| cargo_ship.section_alive(*section))
+                    .count();
+                value_modifier * sections_left as f32 / cargo_ship.section_count() as f32
+            })
+            .sum();
+        !convoy.ships.is_empty()
+    });
+}
+
+#[cfg(test)]
+mod convoys_tests {
+    use super::*;
+
+    #[test]
+    fn soonest_arrival() {
+        let (a, b, c) = (
+            Entity::from_raw(0),
+            Entity::from_raw(1),
+            Entity::from_raw(2),
+        );
+        let convoy = |id, leg| Convoy {
+            id,
+            ships: vec!["Freighter".to_string(); 2],
+            manifest: Manifest::Ore,
+            manifest_value: 3.0,
+            escort_level: 0,
+            leg,
+        };
+        let convoys = Convoys {
+            next_id: 3,
+            convoys: vec![
+                convoy(
+                    0,
+                    ConvoyLeg::InTransit {
+                        from: a,
+                        to: b,
+                        arrives_in: 12.0,
+                    },
+                ),
+                convoy(
+                    1,
+                    ConvoyLeg::InTransit {
+                        from: c,
+                        to: b,
+                        arrives_in: 4.0,
+                    },
+                ),
+                convoy(
+                    2,
+                    ConvoyLeg::Docked {
+                        system: b,
+                        departs_in: 1.0,
+                    },
+                ),
+            ],
+        };
+        assert_eq!(convoys.arrival_time(b), Some(4.0));
+        assert_eq!(convoys.arrival_time(a), None);
+        assert_eq!(convoys.docked_count(b), 1);
+        assert_eq!(convoys.convoys[0].value_modifier(), 1.5);
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{
+    effects::SoundEffect,
+    fighters::{
+        attack, fighter_bundle, fighter_sprite, fly_towards, intercept, Fighter, ATTACK_RANGE,
+        FIGHTER_MASS, FIGHTER_MAX_SPEED, FIGHTER_RADIUS,
+    },
+    physics::PhysicsSet,
+    prelude::*,
+};
+
+pub struct EscortsPlugin;
+
+impl Plugin for EscortsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            escort_ai_system
+                .run_if(in_state(GameState::Playing))
+                .in_set(PhysicsSet::Forces),
+        )
+        .add_systems(
+            Update,
+            dress_escorts.run_if(resource_exists::<GameAssets>()),
+        );
+    }
+}
+
+/// A `Fighter` flying formation on a cargo ship until someone picks a fight with it.
+#[derive(Component, Debug)]
+pub struct Escort {
+    pub ward: Entity,
+    /// Where to fly relative to the ward, in the ward's direction of travel.
+    pub slot: Vec2,
+    pub intercepting: bool,
+}
+
+const ESCORT_HULL: f32 = 25.0;
+const ESCORT_INFAMY: f32 = 2.0;
+// Each slice of a convoy's manifest worth this much buys one more escort.
+const VALUE_PER_ESCORT: f32 = 2.0;
+const MAX_ESCORTS: usize = 6;
+// How hard escorts chase their slot, in units per second per unit out of position.
+const FORMATION_GAIN: f32 = 1.5;
+// Ahead, abeam and astern of the ward, on either side.
+const FORMATION_SLOTS: [Vec2; 6] = [
+    Vec2::new(250.0, 150.0),
+    Vec2::new(250.0, -150.0),
+    Vec2::new(0.0, 250.0),
+    Vec2::new(0.0, -250.0),
+    Vec2::new(-300.0, 150.0),
+    Vec2::new(-300.0, -150.0),
+];
+
+/// How many escorts a convoy gets, from how heavily guarded it is and how much it carries.
+pub fn escort_count(escort_level: u32, manifest_value: f32) -> usize {
+    (escort_level as usize + (manifest_value / VALUE_PER_ESCORT).floor() as usize).min(MAX_ESCORTS)
+}
+
+/// Spreads `count` escorts across `wards`, filling the front slots of every ward first.
+pub fn spawn_escorts(commands: &mut Commands, wards: &[(Entity, Transform)], count: usize) {
+    if wards.is_empty() {
+        return;
+    }
+    for idx in 0..count {
+        let (ward, ward_transform) = wards[idx % wards.len()];
+        let slot = FORMATION_SLOTS[(idx / wards.len()) % FORMATION_SLOTS.len()];
+        // Before they get moving, wards are headed for the middle of the arena.
+        let forward = (-ward_transform.translation.truncate()).normalize_or_zero();
+        let position = ward_transform.translation.truncate() + formation_offset(forward, slot);
+        let mut inertia = InertiaVolume::new(FIGHTER_MASS, FIGHTER_RADIUS);
+        inertia.set_rotation(forward.y.atan2(forward.x));
+        spawn_escort(
+            commands,
+            Transform::from_translation(position.extend(0.)),
+            inertia,
+            Escort {
+                ward,
+                slot,
+                intercepting: false,
+            },
+        );
+    }
+}
+
+/// Spawns an escort fighter without any visuals.
+pub fn spawn_escort(
+    commands: &mut Commands,
+    transform: Transform,
+    inertia: InertiaVolume,
+    escort: Escort,
+) -> Entity {
+    commands
+        .spawn((
+            fighter_bundle(transform, inertia, Fighter::new(ESCORT_HULL, ESCORT_INFAMY)),
+            escort,
+        ))
+        .id()
+}
+
+fn formation_offset(forward: Vec2, slot: Vec2) -> Vec2 {
+    let forward = if forward == Vec2::ZERO {
+        Vec2::X
+    } else {
+        forward
+    };
+    forward * slot.x + forward.perp() * slot.y
+}
+
+fn dress_escorts(
+    mut commands: Commands,
+    escorts: Query<Entity, Added<Escort>>,
+    game_assets: Res<GameAssets>,
+) {
+    for entity in escorts.iter() {
+        commands.add(InsertSafe {
+            entity,
+            bundle: fighter_sprite(&game_assets, Color::rgba(10., 5., 1., 1.)),
+        });
+    }
+}
+
+fn escort_ai_system(
+    fixed_time: Res<FixedTime>,
+    mut escorts: Query<
+        (&mut Escort, &mut Fighter, &Transform, &mut InertiaVolume),
+        (Without<Player>, Without<CargoShip>),
+    >,
+    wards: Query<(&CargoShip, &Transform, &InertiaVolume), Without<Escort>>,
+    players: Query<(&Transform, &InertiaVolume), (With<Player>, Without<Escort>)>,
+    mut commands: Commands,
+    mut sound_effects: EventWriter<SoundEffect>,
+) {
+    let dt = fixed_time.period.as_secs_f32();
+    let player = players.get_single().ok();
+    for (mut escort, mut fighter, transform, mut inertia) in escorts.iter_mut() {
+        let position = transform.translation.truncate();
+        let ward = wards.get(escort.ward).ok();
+        // Once the ward is under attack, or gone, there's nothing left to guard.
+        if ward.map_or(true, |(cargo_ship, ..)| cargo_ship.aggressed) {
+            escort.intercepting = true;
+        }
+        match (escort.intercepting, ward, player) {
+            (true, _, Some(player)) => {
+                if player.0.translation.truncate().distance(position) <= ATTACK_RANGE {
+                    attack(
+                        &mut fighter,
+                        position,
+                        &mut inertia,
+                        player,
+                        dt,
+                        &mut commands,
+                        &mut sound_effects,
+                    );
+                } else {
+                    intercept(position, &mut inertia, player, dt);
+                }
+            }
+            (false, Some((_, ward_transform, ward_inertia)), _) => {
+                let ward_position = ward_transform.translation.truncate();
+                let slot_position = ward_position
+                    + formation_offset(ward_inertia.velocity.normalize_or_zero(), escort.slot);
+                let desired_velocity = (ward_inertia.velocity
+                    + (slot_position - position) * FORMATION_GAIN)
+                    .clamp_length_max(FIGHTER_MAX_SPEED);
+                let heading = if desired_velocity.length() > 1.0 {
+                    desired_velocity
+                } else {
+                    ward_inertia.velocity
+                };
+                fly_towards(&mut inertia, heading, desired_velocity.length(), dt);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod escorts_tests {
+    use super::*;
+    use crate::test_harness::Simulation;
+
+    #[test]
+    fn more_escorts_for_richer_convoys() {
+        assert_eq!(escort_count(0, 1.0), 0);
+        assert!(escort_count(0, 5.0) > escort_count(0, 1.0));
+        assert!(escort_count(2, 1.0) > escort_count(0, 1.0));
+        assert_eq!(escort_count(2, 100.0), MAX_ESCORTS);
+    }
+
+    #[test]
+    fn escort_peels_off_when_ward_aggressed() {
+        let mut simulation = Simulation::new();
+        let ward = simulation.spawn(|commands| {
+            let mut inertia = InertiaVolume::new(1.0, 0.0);
+            inertia.velocity = Vec2::new(20.0, 0.0);
+            let ward = spawn_cargo_ship(
+                commands,
+                Transform::default(),
+                inertia,
+                CargoShip::new(&ShipClass::freighter(), Manifest::Ore, 1.0),
+            );
+            spawn_escorts(commands, &[(ward, Transform::default())], 1);
+            ward
+        });
+        simulation.step(300);
+        let mut escorts = simulation.app.world.query::<(&Escort, &Transform)>();
+        let (escort, transform) = escorts.single(&simulation.app.world);
+        assert!(!escort.intercepting);
+        let ward_position = simulation.get::<Transform>(ward).unwrap().translation;
+        let slot_position = ward_position.truncate() + FORMATION_SLOTS[0];
+        assert!(transform.translation.truncate().distance(slot_position) < 50.0);
+
+        simulation.get_mut::<CargoShip>(ward).unwrap().aggressed = true;
+        simulation.step(1);
+        let (escort, _) = escorts.single(&simulation.app.world);
+        assert!(escort.intercepting);
+    }
+}
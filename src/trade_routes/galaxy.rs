@@ -0,0 +1,277 @@
+use rand::seq::SliceRandom;
+
+use crate::{home::Hideout, prelude::*};
+
+use super::{spawn_system, SystemKind};
+
+pub const GALAXY_SIZE: f32 = 1000.0;
+const MIN_SYSTEM_SPACING: f32 = 120.0;
+// Darts thrown per system before the spacing is relaxed.
+const PLACEMENT_ATTEMPTS: usize = 30;
+// How much the spacing shrinks each time the darts run out.
+const SPACING_FALLOFF: f32 = 0.9;
+// Hideouts besides the one the player starts with.
+const EXTRA_HIDEOUTS: usize = 2;
+const ASTEROID_SYSTEMS: usize = 10;
+const CARGO_SHIP_SYSTEMS: usize = 10;
+const MAX_EXTRA_LANE_LENGTH: f32 = 300.0;
+const EXTRA_LANE_CHANCE: f64 = 0.3;
+
+const NAME_STARTS: [&str; 20] = [
+    "Al", "Bel", "Cor", "Dra", "Eri", "Fen", "Gal", "Hy", "Ix", "Kep", "Lyr", "Mir", "Nox", "Or",
+    "Pax", "Ryn", "Sol", "Tau", "Vex", "Zan",
+];
+const NAME_ENDS: [&str; 12] = [
+    "ara", "ion", "ux", "eth", "is", "on", "ora", "ax", "une", "ima", "os", "enti",
+];
+const NAME_SUFFIXES: [&str; 4] = [" Prime", " Minor", " II", " IV"];
+
+#[derive(Component, Debug)]
+pub struct SystemName(pub String);
+
+/// The systems a hyperdrive can reach from this one.
+#[derive(Component, Debug, Default)]
+pub struct Lanes(pub Vec<Entity>);
+
+#[derive(Debug, Clone)]
+pub struct GalaxySystem {
+    pub name: String,
+    pub location: Vec2,
+    pub kind: SystemKind,
+}
+
+/// The star map: named systems, and the trade lanes between them as pairs of indices.
+#[derive(Debug, Clone, Default)]
+pub struct Galaxy {
+    pub systems: Vec<GalaxySystem>,
+    pub lanes: Vec<(usize, usize)>,
+}
+
+impl Galaxy {
+    pub fn generate(rng: &mut impl Rng) -> Self {
+        let locations = scatter_systems(
+            rng,
+            1 + EXTRA_HIDEOUTS + ASTEROID_SYSTEMS + CARGO_SHIP_SYSTEMS,
+            MIN_SYSTEM_SPACING,
+        );
+        // The starting hideout is the system closest to where the player starts.
+        let home = (0..locations.len())
+            .min_by(|a, b| {
+                locations[*a]
+                    .length_squared()
+                    .total_cmp(&locations[*b].length_squared())
+            })
+            .unwrap();
+        let mut others = (0..locations.len())
+            .filter(|idx| *idx != home)
+            .collect::<Vec<_>>();
+        others.shuffle(rng);
+        let mut kinds = vec![SystemKind::CargoShips; locations.len()];
+        kinds[home] = SystemKind::Home(Hideout::starting(rng));
+        for (order, idx) in others.into_iter().enumerate() {
+            kinds[idx] = if order < EXTRA_HIDEOUTS {
+                SystemKind::Home(Hideout::random(rng))
+            } else if order < EXTRA_HIDEOUTS + ASTEROID_SYSTEMS {
+                SystemKind::Asteroids(rng.gen_range(5..8))
+            } else {
+                SystemKind::CargoShips
+            };
+        }
+        let names = system_names(rng, locations.len());
+        let lanes = connect_systems(rng, &locations);
+        Self {
+            systems: names
+                .into_iter()
+                .zip(locations)
+                .zip(kinds)
+                .map(|((name, location), kind)| GalaxySystem {
+                    name,
+                    location,
+                    kind,
+                })
+                .collect(),
+            lanes,
+        }
+    }
+
+    pub fn neighbours(&self, system: usize) -> impl Iterator<Item = usize> + '_ {
+        self.lanes.iter().filter_map(move |(a, b)| {
+            if *a == system {
+                Some(*b)
+            } else if *b == system {
+                Some(*a)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// Throws darts across the whole galaxy, keeping those at least `spacing` from every system
+/// so far. If the galaxy is too crowded to fit `count` systems, the spacing is relaxed
+/// until they do fit, so there are always exactly `count` of them.
+fn scatter_systems(rng: &mut impl Rng, count: usize, mut spacing: f32) -> Vec<Vec2> {
+    let half_size = GALAXY_SIZE / 2.;
+    let mut locations: Vec<Vec2> = Vec::with_capacity(count);
+    let mut attempts = 0;
+    while locations.len() < count {
+        if attempts >= count * PLACEMENT_ATTEMPTS {
+            attempts = 0;
+            spacing *= SPACING_FALLOFF;
+        }
+        attempts += 1;
+        let candidate = Vec2::new(
+            rng.gen_range(-half_size..half_size),
+            rng.gen_range(-half_size..half_size),
+        );
+        if locations
+            .iter()
+            .all(|location| location.distance(candidate) >= spacing)
+        {
+            locations.push(candidate);
+        }
+    }
+    locations
+}
+
+/// A minimum spanning tree, so every system is reachable, plus some short extra lanes for loops.
+fn connect_systems(rng: &mut impl Rng, locations: &[Vec2]) -> Vec<(usize, usize)> {
+    let mut lanes = Vec::new();
+    if locations.is_empty() {
+        return lanes;
+    }
+    // Prim's algorithm: the closest system to the tree so far, and which tree system it connects to.
+    let mut closest = locations
+        .iter()
+        .map(|location| (location.distance(locations[0]), 0))
+        .collect::<Vec<_>>();
+    let mut in_tree = vec![false; locations.len()];
+    in_tree[0] = true;
+    for _ in 1..locations.len() {
+        let next = (0..locations.len())
+            .filter(|idx| !in_tree[*idx])
+            .min_by(|a, b| closest[*a].0.total_cmp(&closest[*b].0))
+            .unwrap();
+        in_tree[next] = true;
+        lanes.push((closest[next].1.min(next), closest[next].1.max(next)));
+        for idx in 0..locations.len() {
+            let distance = locations[idx].distance(locations[next]);
+            if !in_tree[idx] && distance < closest[idx].0 {
+                closest[idx] = (distance, next);
+            }
+        }
+    }
+    for a in 0..locations.len() {
+        for b in (a + 1)..locations.len() {
+            if !lanes.contains(&(a, b))
+                && locations[a].distance(locations[b]) < MAX_EXTRA_LANE_LENGTH
+                && rng.gen_bool(EXTRA_LANE_CHANCE)
+            {
+                lanes.push((a, b));
+            }
+        }
+    }
+    lanes
+}
+
+fn system_names(rng: &mut impl Rng, count: usize) -> Vec<String> {
+    let mut names = Vec::with_capacity(count);
+    while names.len() < count {
+        let mut name = format!(
+            "{}{}",
+            NAME_STARTS.choose(rng).unwrap(),
+            NAME_ENDS.choose(rng).unwrap()
+        );
+        if rng.gen_bool(0.2) {
+            name.push_str(NAME_SUFFIXES.choose(rng).unwrap());
+        }
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Spawns every system in the galaxy and links up their lanes, returning the systems in order.
+pub fn spawn_galaxy(
+    galaxy: &Galaxy,
+    game_assets: &Res<GameAssets>,
+    commands: &mut Commands,
+) -> Vec<Entity> {
+    let entities = galaxy
+        .systems
+        .iter()
+        .map(|system| {
+            let entity = spawn_system(system.location, system.kind.clone(), game_assets, commands);
+            commands
+                .entity(entity)
+                .insert(SystemName(system.name.clone()));
+            entity
+        })
+        .collect::<Vec<_>>();
+    for (idx, entity) in entities.iter().enumerate() {
+        commands.entity(*entity).insert(Lanes(
+            galaxy
+                .neighbours(idx)
+                .map(|neighbour| entities[neighbour])
+                .collect(),
+        ));
+    }
+    entities
+}
+
+#[cfg(test)]
+mod galaxy_tests {
+    use rand_pcg::Pcg64;
+
+    use super::*;
+
+    #[test]
+    fn every_system_is_reachable() {
+        let galaxy = Galaxy::generate(&mut Pcg64::new(2444, 0));
+        assert_eq!(
+            galaxy.systems.len(),
+            1 + EXTRA_HIDEOUTS + ASTEROID_SYSTEMS + CARGO_SHIP_SYSTEMS
+        );
+        let mut visited = vec![false; galaxy.systems.len()];
+        let mut frontier = vec![0];
+        while let Some(system) = frontier.pop() {
+            if !visited[system] {
+                visited[system] = true;
+                frontier.extend(galaxy.neighbours(system));
+            }
+        }
+        assert!(visited.iter().all(|visited| *visited));
+    }
+
+    #[test]
+    fn crowded_galaxies_still_fit_every_system() {
+        // Far more than fit at this spacing.
+        let locations = scatter_systems(&mut Pcg64::new(2444, 0), 200, MIN_SYSTEM_SPACING);
+        assert_eq!(locations.len(), 200);
+    }
+
+    #[test]
+    fn systems_are_spaced_and_named() {
+        let galaxy = Galaxy::generate(&mut Pcg64::new(2444, 0));
+        for (a, system) in galaxy.systems.iter().enumerate() {
+            for other in galaxy.systems.iter().skip(a + 1) {
+                assert!(system.location.distance(other.location) >= MIN_SYSTEM_SPACING);
+                assert_ne!(system.name, other.name);
+            }
+        }
+        let hideouts = galaxy
+            .systems
+            .iter()
+            .filter_map(|system| match &system.kind {
+                SystemKind::Home(hideout) => Some(hideout),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(hideouts.len(), 1 + EXTRA_HIDEOUTS);
+        assert_eq!(
+            hideouts.iter().filter(|hideout| hideout.unlocked).count(),
+            1
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
     game_state,
+    heat::Heat,
     home::{Career, HomeInSystem},
     player,
     prelude::*,
@@ -9,8 +10,19 @@ pub const ARENA_SIZE: f32 = 1000.0;
 pub const HYPERDRIVE_SPEED: f32 = 500.0;
 
 mod cargo_ships;
+mod convoys;
+mod escorts;
+mod galaxy;
+mod ship_classes;
+mod snapshot;
+mod steering;
 mod system;
 pub use cargo_ships::*;
+pub use convoys::*;
+pub use escorts::*;
+pub use galaxy::*;
+pub use ship_classes::*;
+pub use snapshot::*;
 pub use system::*;
 
 pub struct TradeRoutesPlugin;
@@ -26,12 +38,9 @@ impl Plugin for TradeRoutesPlugin {
                 pick_hyperdrive_target.run_if(in_state(GameState::Playing)),
                 engage_hyperdrive_system.run_if(in_state(GameState::Playing)),
                 initialize_local_region.run_if(in_state(GameState::Hyperdrive)),
-                cargo_ship_jet_animation_system,
-                cargo_ship_defense_system.run_if(in_state(GameState::Playing)),
-                cargo_ship_escape_system.run_if(in_state(GameState::Playing)),
-                cargo_ship_drop_system.run_if(in_state(GameState::Playing)),
             ),
-        );
+        )
+        .add_plugins((CargoShipsPlugin, ConvoysPlugin, EscortsPlugin));
     }
 }
 
@@ -58,14 +67,16 @@ fn handle_music(
     cargo_ships: Query<&CargoShip>,
     home_system: Query<(&HomeInSystem, Option<&CurrentSystemRegion>)>,
     player: Query<&player::Player>,
+    heat: Res<Heat>,
 ) {
     let mut current_song = ActiveSong::Space;
-    if cargo_ships.iter().any(|cargo_ship| cargo_ship.aggressed) {
+    // Space doesn't feel calm with the authorities looking for you.
+    if heat.is_hot() || cargo_ships.iter().any(|cargo_ship| cargo_ship.aggressed) {
         current_song = ActiveSong::Engagement;
     }
     if home_system
         .iter()
-        .any(|(_, current_system_region)| current_system_region.is_some())
+        .any(|(home, current_system_region)| home.0.unlocked && current_system_region.is_some())
     {
         current_song = ActiveSong::Home;
     }
@@ -0,0 +1,123 @@
+use anyhow::bail;
+use rand::distributions::{Distribution, WeightedIndex};
+use serde::Deserialize;
+
+use crate::{damage::Resistances, prelude::*};
+
+/// Every kind of cargo ship flying the trade lanes, loaded from `assets/ship_classes.ron`.
+#[derive(Resource, Debug, Clone, Deserialize)]
+pub struct ShipClassCatalogue {
+    pub classes: Vec<ShipClass>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ShipClass {
+    pub id: String,
+    pub name: String,
+    /// Which of the `Skeletons` the ship is drawn with.
+    pub skeleton: String,
+    /// Skeleton bones this class doesn't use, hidden when the ship is dressed.
+    #[serde(default)]
+    pub hidden_bones: Vec<String>,
+    pub spawn_weight: f32,
+    /// The bare hull, without any cargo sections.
+    pub hull_mass: f32,
+    pub section_mass: f32,
+    pub thrust: f32,
+    /// Seconds to spool up the hyperdrive.
+    pub jump_time: f32,
+    /// Protects every cargo section.
+    #[serde(default)]
+    pub section_armour: Resistances,
+    pub sections: Vec<SectionDefinition>,
+    pub turrets: Vec<TurretDefinition>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SectionDefinition {
+    /// Sections without a bone are drawn as plain cargo pods.
+    #[serde(default)]
+    pub bone: Option<String>,
+    #[serde(default)]
+    pub damage_slot: Option<String>,
+    #[serde(default)]
+    pub hit_animation: Option<String>,
+    pub offset: (f32, f32),
+    pub health: f32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TurretDefinition {
+    pub bone: String,
+    pub range: f32,
+    /// Seconds between shots.
+    pub cooldown: f32,
+}
+
+impl ShipClass {
+    pub fn mass(&self) -> f32 {
+        self.hull_mass + self.section_mass * self.sections.len() as f32
+    }
+}
+
+impl ShipClassCatalogue {
+    pub fn from_ron(contents: &str) -> anyhow::Result<Self> {
+        let catalogue: Self = ron::from_str(contents)?;
+        for class in catalogue.classes.iter() {
+            if !Skeletons::NAMES.contains(&class.skeleton.as_str()) {
+                bail!("{} has no skeleton called {:?}", class.id, class.skeleton);
+            }
+        }
+        Ok(catalogue)
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ShipClass> {
+        self.classes.iter().find(|class| class.id == id)
+    }
+
+    /// Picks a class according to the spawn weights, if there are any classes at all.
+    pub fn random(&self, rng: &mut impl Rng) -> Option<&ShipClass> {
+        let weights =
+            WeightedIndex::new(self.classes.iter().map(|class| class.spawn_weight)).ok()?;
+        Some(&self.classes[weights.sample(rng)])
+    }
+}
+
+#[cfg(test)]
+impl ShipClass {
+    /// The plain eight section freighter, for tests.
+    pub fn freighter() -> Self {
+        ShipClassCatalogue::from_ron(include_str!("../../assets/ship_classes.ron"))
+            .unwrap()
+            .get("Freighter")
+            .unwrap()
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod ship_classes_tests {
+    use super::*;
+
+    #[test]
+    fn catalogue_parses() {
+        let catalogue =
+            ShipClassCatalogue::from_ron(include_str!("../../assets/ship_classes.ron")).unwrap();
+        for class in catalogue.classes.iter() {
+            assert!(!class.sections.is_empty(), "{}", class.id);
+            assert!(class.spawn_weight >= 0.0, "{}", class.id);
+        }
+        assert_eq!(catalogue.get("BulkHauler").unwrap().sections.len(), 16);
+        let freighter = catalogue.get("Freighter").unwrap();
+        assert_eq!(freighter.sections[0].bone.as_deref(), Some("cargo0"));
+        assert_eq!(freighter.mass(), 10000.0);
+    }
+
+    #[test]
+    fn unknown_skeletons_are_rejected() {
+        let contents = include_str!("../../assets/ship_classes.ron")
+            .replace("\"cargo_ship\"", "\"cargo_shp\"");
+        let err = ShipClassCatalogue::from_ron(&contents).err().unwrap();
+        assert!(err.to_string().contains("cargo_shp"));
+    }
+}
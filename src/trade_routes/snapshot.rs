@@ -0,0 +1,154 @@
+use crate::{jamming::spawn_jammer, prelude::*};
+
+use super::{dress_cargo_ship, spawn_cargo_ship};
+
+/// What was left behind in a system when the player last jumped out of it. Snapshots only last
+/// the session: they aren't saved, so a loaded career finds every system freshly generated.
+#[derive(Component, Clone, Default)]
+pub struct SystemSnapshot {
+    pub contents: Vec<SnapshotEntity>,
+}
+
+#[derive(Clone)]
+pub struct SnapshotEntity {
+    pub transform: Transform,
+    pub inertia: InertiaVolume,
+    pub kind: SnapshotKind,
+}
+
+#[derive(Clone)]
+pub enum SnapshotKind {
+    Pickup {
+        pickup: Pickup,
+        jamming_radius: Option<f32>,
+    },
+    Jammer {
+        radius: f32,
+    },
+    CargoShip(CargoShip),
+}
+
+impl SystemSnapshot {
+    /// Keeps pickups, deployed jammers and wrecks. Bullets and effects are not worth keeping,
+    /// and ships that can still fly belong to their convoy.
+    pub fn take<'a>(
+        regional: impl Iterator<
+            Item = (
+                &'a Transform,
+                &'a InertiaVolume,
+                Option<&'a Pickup>,
+                Option<&'a Jammer>,
+                Option<&'a CargoShip>,
+            ),
+        >,
+    ) -> Self {
+        let contents = regional
+            .filter_map(|(transform, inertia, m_pickup, m_jammer, m_cargo_ship)| {
+                let kind = match (m_pickup, m_jammer, m_cargo_ship) {
+                    (Some(pickup), m_jammer, _) => SnapshotKind::Pickup {
+                        pickup: pickup.clone(),
+                        jamming_radius: m_jammer.map(|jammer| jammer.radius),
+                    },
+                    (None, Some(jammer), _) => SnapshotKind::Jammer {
+                        radius: jammer.radius,
+                    },
+                    (None, None, Some(cargo_ship)) if cargo_ship.wrecked() => {
+                        SnapshotKind::CargoShip(cargo_ship.clone())
+                    }
+                    _ => return None,
+                };
+                Some(SnapshotEntity {
+                    transform: *transform,
+                    inertia: inertia.clone(),
+                    kind,
+                })
+            })
+            .collect();
+        Self { contents }
+    }
+
+    pub fn restore(
+        &self,
+        commands: &mut Commands,
+        game_assets: &GameAssets,
+        skeletons: &Skeletons,
+    ) {
+        for entity in self.contents.iter() {
+            match &entity.kind {
+                SnapshotKind::Pickup {
+                    pickup,
+                    jamming_radius,
+                } => {
+                    let pickup = spawn_pickup(
+                        commands,
+                        entity.transform,
+                        entity.inertia.clone(),
+                        pickup.clone(),
+                    );
+                    if let Some(radius) = jamming_radius {
+                        commands.entity(pickup).insert(Jammer {
+                            radius: *radius,
+                            progress: 0.0,
+                        });
+                    }
+                }
+                SnapshotKind::Jammer { radius } => {
+                    let jammer = spawn_jammer(
+                        commands,
+                        entity.transform.translation,
+                        entity.inertia.velocity,
+                        *radius,
+                    );
+                    commands.entity(jammer).insert(entity.inertia.clone());
+                }
+                SnapshotKind::CargoShip(cargo_ship) => {
+                    let ship = spawn_cargo_ship(
+                        commands,
+                        entity.transform,
+                        entity.inertia.clone(),
+                        cargo_ship.clone(),
+                    );
+                    dress_cargo_ship(
+                        commands,
+                        ship,
+                        entity.transform,
+                        &cargo_ship.class,
+                        game_assets,
+                        skeletons,
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+
+    #[test]
+    fn keeps_loot_and_wrecks() {
+        let transform = Transform::from_xyz(10.0, 20.0, 0.0);
+        let inertia = InertiaVolume::new(1.0, 8.0);
+        let salvage = Pickup::Salvage {
+            commodity: Commodity::Ore,
+            mass: 1.0,
+            value: 5.0,
+        };
+        let mut wreck = CargoShip::new(&ShipClass::freighter(), Manifest::Ore, 1.0);
+        wreck.sections_destroyed = vec![true; 8];
+        let cargo_ship = CargoShip::new(&ShipClass::freighter(), Manifest::Ore, 1.0);
+        let snapshot = SystemSnapshot::take(
+            [
+                (&transform, &inertia, Some(&salvage), None, None),
+                (&transform, &inertia, None, None, Some(&wreck)),
+                (&transform, &inertia, None, None, Some(&cargo_ship)),
+                // A bullet.
+                (&transform, &inertia, None, None, None),
+            ]
+            .into_iter(),
+        );
+        assert_eq!(snapshot.contents.len(), 2);
+        assert_eq!(snapshot.contents[0].transform, transform);
+    }
+}
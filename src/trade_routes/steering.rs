@@ -0,0 +1,165 @@
+//! Steering behaviours for cargo ships. Each behaviour proposes a direction to travel in, and
+//! `blend` mixes them by priority into a single heading for `steer` to fly.
+
+use crate::prelude::*;
+
+// Projectiles passing further away than this aren't worth dodging.
+const EVADE_RADIUS: f32 = 150.0;
+// How far ahead, in seconds, ships watch incoming fire.
+const EVADE_HORIZON: f32 = 2.0;
+// Aim a little past the edge of a jamming field, so the ship doesn't stall on the boundary.
+const JAMMER_EXIT_MARGIN: f32 = 100.0;
+// How quickly the ship's rotation catches up with the heading it wants.
+const TURN_GAIN: f32 = 2.0;
+
+pub fn seek(position: Vec2, target: Vec2) -> Vec2 {
+    (target - position).normalize_or_zero()
+}
+
+pub fn flee(position: Vec2, threat: Vec2) -> Vec2 {
+    (position - threat).normalize_or_zero()
+}
+
+/// A sideways dodge from incoming `projectiles`, given as positions and velocities. Closer
+/// calls push harder, up to a unit vector.
+pub fn evade_projectiles(
+    position: Vec2,
+    velocity: Vec2,
+    projectiles: impl Iterator<Item = (Vec2, Vec2)>,
+) -> Option<Vec2> {
+    let mut dodge = Vec2::ZERO;
+    for (projectile_position, projectile_velocity) in projectiles {
+        let offset = position - projectile_position;
+        let relative_velocity = projectile_velocity - velocity;
+        let speed_sq = relative_velocity.length_squared();
+        if speed_sq == 0. {
+            continue;
+        }
+        let time_to_closest = offset.dot(relative_velocity) / speed_sq;
+        if !(0.0..=EVADE_HORIZON).contains(&time_to_closest) {
+            continue;
+        }
+        // Where the ship will be relative to the projectile when it passes closest.
+        let miss = offset - relative_velocity * time_to_closest;
+        let miss_distance = miss.length();
+        if miss_distance >= EVADE_RADIUS {
+            continue;
+        }
+        let away = if miss_distance > 0. {
+            miss / miss_distance
+        } else {
+            relative_velocity.perp().normalize()
+        };
+        dodge += away * (1.0 - miss_distance / EVADE_RADIUS);
+    }
+    if dodge == Vec2::ZERO {
+        None
+    } else {
+        Some(dodge.clamp_length_max(1.0))
+    }
+}
+
+/// The closest point outside every jamming field containing `position`, given as centres
+/// and radii. `None` if the ship isn't being jammed.
+pub fn nearest_jammer_exit(
+    position: Vec2,
+    jammers: impl Iterator<Item = (Vec2, f32)>,
+) -> Option<Vec2> {
+    jammers
+        .filter(|(center, radius)| center.distance(position) < *radius)
+        .map(|(center, radius)| {
+            let outwards = (position - center).try_normalize().unwrap_or(Vec2::X);
+            center + outwards * (radius + JAMMER_EXIT_MARGIN)
+        })
+        .min_by(|a, b| a.distance(position).total_cmp(&b.distance(position)))
+}
+
+/// Mixes behaviours in priority order. Each gets as much of the unit steering budget as its
+/// weight asks for, until the budget runs out and lower priorities are ignored.
+pub fn blend(prioritized: impl IntoIterator<Item = (Option<Vec2>, f32)>) -> Vec2 {
+    let mut steering = Vec2::ZERO;
+    let mut budget = 1.0;
+    for (m_direction, weight) in prioritized {
+        let Some(direction) = m_direction else {
+            continue;
+        };
+        if direction == Vec2::ZERO {
+            continue;
+        }
+        let share = weight.min(budget);
+        steering += direction.normalize() * share;
+        budget -= share;
+        if budget <= 0. {
+            break;
+        }
+    }
+    steering
+}
+
+/// Turns towards `desired` no faster than `turn_rate`, and thrusts in proportion to how
+/// well lined up the ship is and how strongly it wants to go.
+pub fn steer(inertia: &mut InertiaVolume, desired: Vec2, turn_rate: f32, thrust: f32, dt: f32) {
+    if desired == Vec2::ZERO {
+        inertia.rotation_velocity = 0.;
+        return;
+    }
+    let mut difference = desired.y.atan2(desired.x) - inertia.rotation();
+    while difference > PI {
+        difference -= PI * 2.;
+    }
+    while difference < -PI {
+        difference += PI * 2.;
+    }
+    inertia.rotation_velocity = (difference * TURN_GAIN).clamp(-turn_rate, turn_rate);
+    let forward = Vec2::from_angle(inertia.rotation());
+    let alignment = forward.dot(desired.normalize()).max(0.);
+    inertia.apply_thrust_force(thrust * alignment * desired.length().min(1.0), dt);
+}
+
+#[cfg(test)]
+mod steering_tests {
+    use super::*;
+
+    #[test]
+    fn heads_for_the_nearest_jammer_edge() {
+        let jammers = [(Vec2::ZERO, 500.0), (Vec2::new(300.0, 0.0), 500.0)];
+        let exit = nearest_jammer_exit(Vec2::new(-100.0, 0.0), jammers.into_iter()).unwrap();
+        assert_eq!(exit, Vec2::new(-500.0 - JAMMER_EXIT_MARGIN, 0.0));
+        assert_eq!(
+            nearest_jammer_exit(Vec2::new(2000.0, 0.0), jammers.into_iter()),
+            None
+        );
+    }
+
+    #[test]
+    fn dodges_sideways_from_incoming_fire() {
+        // A laser coming straight down the x axis, just above the ship.
+        let dodge = evade_projectiles(
+            Vec2::ZERO,
+            Vec2::ZERO,
+            [(Vec2::new(-500.0, 10.0), Vec2::new(1000.0, 0.0))].into_iter(),
+        )
+        .unwrap();
+        assert!(dodge.y < 0. && dodge.x.abs() < 0.001);
+        // One going away doesn't matter.
+        assert_eq!(
+            evade_projectiles(
+                Vec2::ZERO,
+                Vec2::ZERO,
+                [(Vec2::new(500.0, 10.0), Vec2::new(1000.0, 0.0))].into_iter(),
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn higher_priorities_win() {
+        let steering = blend([
+            (None, 1.0),
+            (Some(Vec2::Y), 0.75),
+            (Some(Vec2::X), 0.5),
+            (Some(-Vec2::Y), 1.0),
+        ]);
+        assert_eq!(steering, Vec2::new(0.25, 0.75));
+    }
+}
@@ -1,9 +1,11 @@
+use serde::{Deserialize, Serialize};
+
 use crate::{
-    home::{spawn_home_in_system, Career, HomeInSystem},
+    home::{spawn_home_in_system, Career, Hideout, HomeInSystem},
     prelude::*,
 };
 
-use super::{spawn_cargo_ships, Jammer};
+use super::{spawn_galaxy, Convoys, Galaxy, Jammer, Lanes, SystemName, SystemSnapshot};
 
 #[derive(Component)]
 pub struct SystemLocation {
@@ -26,49 +28,60 @@ pub struct Regional;
 pub struct CargoShipsInSystem;
 
 #[derive(Component)]
-pub struct AsteroidsInSystem(usize);
+pub struct AsteroidsInSystem(pub usize);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SystemKind {
+    Asteroids(usize),
+    CargoShips,
+    Home(Hideout),
+}
 
-pub fn spawn_starting_system(mut commands: Commands, game_assets: Res<GameAssets>) {
-    for _ in 0..10 {
-        spawn_asteroids_in_system(
-            Vec2::new(
-                rand::thread_rng().gen_range((-500.)..500.),
-                rand::thread_rng().gen_range((-500.)..500.),
-            ),
-            &game_assets,
-            &mut commands,
-            rand::thread_rng().gen_range(5..8),
-        );
+impl SystemKind {
+    pub fn of(
+        asteroids: Option<&AsteroidsInSystem>,
+        cargo_ships: Option<&CargoShipsInSystem>,
+        home: Option<&HomeInSystem>,
+    ) -> Option<Self> {
+        match (asteroids, cargo_ships, home) {
+            (Some(asteroids), _, _) => Some(SystemKind::Asteroids(asteroids.0)),
+            (_, Some(_), _) => Some(SystemKind::CargoShips),
+            (_, _, Some(home)) => Some(SystemKind::Home(home.0.clone())),
+            _ => None,
+        }
     }
-    for _ in 0..10 {
-        spawn_cargo_ships_in_system(
-            Vec2::new(
-                rand::thread_rng().gen_range((-500.)..500.),
-                rand::thread_rng().gen_range((-500.)..500.),
-            ),
-            &game_assets,
-            &mut commands,
-        );
+}
+
+pub fn spawn_system(
+    location: Vec2,
+    kind: SystemKind,
+    game_assets: &Res<GameAssets>,
+    commands: &mut Commands,
+) -> Entity {
+    match kind {
+        SystemKind::Asteroids(size) => {
+            spawn_asteroids_in_system(location, game_assets, commands, size)
+        }
+        SystemKind::CargoShips => spawn_cargo_ships_in_system(location, game_assets, commands),
+        SystemKind::Home(hideout) => spawn_home_in_system(location, hideout, game_assets, commands),
     }
-    spawn_home_in_system(
-        Vec2::new(
-            rand::thread_rng().gen_range((-500.)..500.),
-            rand::thread_rng().gen_range((-500.)..500.),
-        ),
-        &game_assets,
-        &mut commands,
-    );
 }
 
-pub fn spawn_asteroid_field(mut commands: Commands, game_assets: Res<GameAssets>, count: usize) {
+pub fn spawn_starting_system(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    mut rng: ResMut<GameRng>,
+) {
+    let galaxy = Galaxy::generate(&mut rng.world);
+    spawn_galaxy(&galaxy, &game_assets, &mut commands);
+}
+
+pub fn spawn_asteroid_field(mut commands: Commands, rng: &mut impl Rng, count: usize) {
     for _ in 0..count {
-        spawn_exotic(
-            rand::thread_rng().gen_range((-ARENA_SIZE)..ARENA_SIZE),
-            rand::thread_rng().gen_range((-ARENA_SIZE)..ARENA_SIZE),
-            &mut commands,
-            game_assets.exotic.clone(),
-            rand::thread_rng().gen_range(5.0..15.0),
-        );
+        let x = rng.gen_range((-ARENA_SIZE)..ARENA_SIZE);
+        let y = rng.gen_range((-ARENA_SIZE)..ARENA_SIZE);
+        let value = rng.gen_range(5.0..15.0);
+        spawn_exotic(x, y, &mut commands, rng, value);
     }
 }
 
@@ -77,33 +90,84 @@ fn spawn_asteroids_in_system(
     game_assets: &Res<GameAssets>,
     mut commands: &mut Commands,
     size: usize,
-) {
+) -> Entity {
     let (indicator, indicator_text) = create_indicator_with_text(commands, game_assets, false);
-    commands.spawn((
-        TransformBundle::default(),
-        SystemLocation { location },
-        AsteroidsInSystem(size),
-        DistantIndicator::new_system(indicator, indicator_text, Vec2::ZERO),
-    ));
+    commands
+        .spawn((
+            TransformBundle::default(),
+            SystemLocation { location },
+            AsteroidsInSystem(size),
+            DistantIndicator::new_system(indicator, indicator_text, Vec2::ZERO),
+        ))
+        .id()
 }
 
 fn spawn_cargo_ships_in_system(
     location: Vec2,
     game_assets: &Res<GameAssets>,
     mut commands: &mut Commands,
-) {
+) -> Entity {
     let (indicator, indicator_text) = create_indicator_with_text(commands, game_assets, false);
-    commands.spawn((
-        TransformBundle::default(),
-        SystemLocation { location },
-        CargoShipsInSystem,
-        DistantIndicator::new_system(indicator, indicator_text, Vec2::ZERO),
-    ));
+    commands
+        .spawn((
+            TransformBundle::default(),
+            SystemLocation { location },
+            CargoShipsInSystem,
+            DistantIndicator::new_system(indicator, indicator_text, Vec2::ZERO),
+        ))
+        .id()
+}
+
+type StarMap<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static SystemLocation,
+        &'static Lanes,
+        Option<&'static CurrentSystemRegion>,
+    ),
+>;
+
+/// Lanes out of the current system, or out of the nearest one before the first jump.
+fn reachable_systems(player_location: Vec2, star_map: &StarMap) -> Vec<Entity> {
+    star_map
+        .iter()
+        .find(|(.., current)| current.is_some())
+        .or_else(|| {
+            star_map.iter().min_by(|(_, a, ..), (_, b, ..)| {
+                a.location
+                    .distance(player_location)
+                    .total_cmp(&b.location.distance(player_location))
+            })
+        })
+        .map(|(_, _, lanes, _)| lanes.0.clone())
+        .unwrap_or_default()
+}
+
+// How much further off the nose the nearest hideout can be and still get picked.
+const NEAREST_HIDEOUT_LEEWAY: f32 = 0.5;
+
+/// The closest unlocked hideout a lane leads to.
+fn nearest_hideout<'a>(
+    player_location: Vec2,
+    reachable: &[Entity],
+    hideouts: impl Iterator<Item = (Entity, Vec2, &'a Hideout)>,
+) -> Option<Entity> {
+    hideouts
+        .filter(|(entity, _, hideout)| hideout.unlocked && reachable.contains(entity))
+        .min_by(|(_, a, _), (_, b, _)| {
+            a.distance(player_location)
+                .total_cmp(&b.distance(player_location))
+        })
+        .map(|(entity, ..)| entity)
 }
 
 pub fn pick_hyperdrive_target(
-    mut player: Query<(&mut Player, &InertiaVolume)>,
+    mut player: Query<(&mut Player, &InertiaVolume, &SystemLocation)>,
     mut indicators: Query<(Entity, &DistantIndicator), Without<CurrentSystemRegion>>,
+    hideouts: Query<(Entity, &SystemLocation, &HomeInSystem)>,
+    star_map: StarMap,
 ) {
     if player.is_empty() {
         return;
@@ -112,13 +176,26 @@ pub fn pick_hyperdrive_target(
     let mut hyperdrive_target = None;
     let player_facing = player.single().1.rotation();
     let player_facing = Vec2::new(player_facing.cos(), player_facing.sin());
+    let player_location = player.single().2.location;
+    let reachable = reachable_systems(player_location, &star_map);
+    // Ending the day matters more than anywhere else, so the closest way home is easiest to pick.
+    let nearest_hideout = nearest_hideout(
+        player_location,
+        &reachable,
+        hideouts
+            .iter()
+            .map(|(entity, location, home)| (entity, location.location, &home.0)),
+    );
     for (system_entity, indicator) in indicators.iter_mut() {
         match indicator {
             DistantIndicator::System {
                 visible, direction, ..
             } => {
-                let distance = direction.normalize().distance(player_facing);
-                if *visible && distance < min_distance {
+                let mut distance = direction.normalize().distance(player_facing);
+                if nearest_hideout == Some(system_entity) {
+                    distance -= NEAREST_HIDEOUT_LEEWAY;
+                }
+                if *visible && reachable.contains(&system_entity) && distance < min_distance {
                     min_distance = distance;
                     hyperdrive_target = Some(system_entity);
                 }
@@ -129,26 +206,37 @@ pub fn pick_hyperdrive_target(
     player.single_mut().0.hyperdrive_target = hyperdrive_target;
 }
 
-const MAX_HYPERDRIVE_TARGETS: usize = 5;
-
 pub fn update_system_indicators(
     player: Query<(&Player, &SystemLocation)>,
     mut indicators: Query<(
         Entity,
         &SystemLocation,
         &mut DistantIndicator,
+        Option<&SystemName>,
         Option<&AsteroidsInSystem>,
         Option<&CargoShipsInSystem>,
         Option<&HomeInSystem>,
         Option<&CurrentSystemRegion>,
     )>,
     mut indicator_texts: Query<&mut Text>,
+    star_map: StarMap,
+    convoys: Res<Convoys>,
 ) {
     if player.is_empty() {
         return;
     }
     let hyperdrive_target = player.single().0.hyperdrive_target;
     let player_location = player.single().1.location;
+    let reachable = reachable_systems(player_location, &star_map);
+    let nearest_hideout = nearest_hideout(
+        player_location,
+        &reachable,
+        indicators
+            .iter()
+            .filter_map(|(entity, location, _, _, _, _, m_home, _)| {
+                m_home.map(|home| (entity, location.location, &home.0))
+            }),
+    );
     let mut sorted_indicators = indicators
         .iter_mut()
         .map(|(entity, system_location, ..)| {
@@ -156,10 +244,17 @@ pub fn update_system_indicators(
         })
         .collect::<Vec<_>>();
     sorted_indicators.sort_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap());
-    let mut idx = 0;
     for (entity, distance) in sorted_indicators.iter() {
-        if let Ok((_, system_location, mut indicator, m_asteroid, m_ship, m_home, m_current)) =
-            indicators.get_mut(*entity)
+        if let Ok((
+            _,
+            system_location,
+            mut indicator,
+            m_name,
+            m_asteroid,
+            m_ship,
+            m_home,
+            m_current,
+        )) = indicators.get_mut(*entity)
         {
             match &mut *indicator {
                 DistantIndicator::System {
@@ -170,33 +265,52 @@ pub fn update_system_indicators(
                 } => {
                     *direction = player_location - system_location.location;
                     if let Ok(mut indicator_text) = indicator_texts.get_mut(*indicator_text) {
+                        let mut traffic = String::new();
+                        let docked = convoys.docked_count(*entity);
+                        if docked > 0 {
+                            traffic.push_str(&format!("\n{} convoy(s) docked", docked));
+                        }
+                        if let Some(arrival_time) = convoys.arrival_time(*entity) {
+                            traffic.push_str(&format!(
+                                "\nConvoy arriving in ~{:.0}s",
+                                arrival_time.ceil()
+                            ));
+                        }
+                        if nearest_hideout == Some(*entity) {
+                            traffic.push_str("\nNearest hideout");
+                        }
                         indicator_text.sections[0].value = format!(
-                            "{:.2}AU ({})",
+                            "{}{:.2}AU ({}){}",
+                            m_name
+                                .map(|name| format!("{} ", name.0))
+                                .unwrap_or_default(),
                             distance,
                             if m_asteroid.is_some() {
-                                "Asteroids"
+                                "Asteroids".to_string()
                             } else if m_ship.is_some() {
-                                "Cargo Ships"
-                            } else if m_home.is_some() {
-                                "Hideout"
+                                "Cargo Ships".to_string()
+                            } else if let Some(home) = m_home {
+                                if home.0.unlocked {
+                                    "Hideout".to_string()
+                                } else {
+                                    format!("Hideout, ${:.0} to buy in", home.0.price)
+                                }
                             } else {
-                                "Unknown"
-                            }
+                                "Unknown".to_string()
+                            },
+                            traffic
                         );
                         if hyperdrive_target == Some(*entity) {
                             indicator_text.sections[0].style.color = Color::YELLOW;
-                        } else {
+                        } else if reachable.contains(entity) {
                             indicator_text.sections[0].style.color = Color::WHITE;
+                        } else {
+                            // Hideouts are always shown, even when no lane leads there.
+                            indicator_text.sections[0].style.color = Color::GRAY;
                         }
                     }
-                    if m_current.is_some() {
-                        *visible = false;
-                    } else if m_home.is_some() {
-                        *visible = true;
-                    } else {
-                        *visible = idx < MAX_HYPERDRIVE_TARGETS;
-                        idx += 1;
-                    }
+                    *visible =
+                        m_current.is_none() && (m_home.is_some() || reachable.contains(entity));
                 }
                 _ => {}
             }
@@ -276,10 +390,23 @@ pub fn initialize_local_region(
         Option<&HomeInSystem>,
     )>,
     regional_entities: Query<(Entity, &Regional)>,
+    regional_contents: Query<
+        (
+            &Transform,
+            &InertiaVolume,
+            Option<&Pickup>,
+            Option<&Jammer>,
+            Option<&CargoShip>,
+        ),
+        (With<Regional>, Without<Player>),
+    >,
+    current_system: Query<Entity, With<CurrentSystemRegion>>,
+    snapshots: Query<&SystemSnapshot>,
     time: Res<Time>,
     mut commands: Commands,
     game_assets: Res<GameAssets>,
     skeletons: Res<Skeletons>,
+    mut rng: ResMut<GameRng>,
 ) {
     if *timeout == 0. {
         *timeout = 5.;
@@ -300,31 +427,30 @@ pub fn initialize_local_region(
         let incoming_direction = (target_location - player_location).normalize();
         player_transform.translation = (incoming_direction * ARENA_SIZE).extend(0.0);
         inertia.velocity = -incoming_direction * HYPERDRIVE_SPEED;
-        // Despawn all regional entities.
+        // Remember what we leave behind, then despawn all regional entities.
+        for old_region in current_system.iter() {
+            commands
+                .entity(old_region)
+                .insert(SystemSnapshot::take(regional_contents.iter()));
+        }
         for (entity, _) in regional_entities.iter() {
             commands.entity(entity).despawn_recursive();
         }
 
+        let snapshot = snapshots.get(new_region).ok();
+        if let Some(snapshot) = snapshot {
+            snapshot.restore(&mut commands, &game_assets, &skeletons);
+        }
         match regions.get(new_region).unwrap() {
             (Some(asteroids), _, _) => {
-                spawn_asteroid_field(commands, game_assets, asteroids.0);
-                next_state.set(GameState::Playing);
-            }
-            (_, Some(_cargo_ships), _) => {
-                spawn_cargo_ships(
-                    commands,
-                    game_assets,
-                    skeletons,
-                    if rand::thread_rng().gen_bool(0.25) {
-                        2
-                    } else if rand::thread_rng().gen_bool(0.33) {
-                        3
-                    } else {
-                        1
-                    },
-                );
+                // Fields don't grow back once they've been mined.
+                if snapshot.is_none() {
+                    spawn_asteroid_field(commands, &mut rng.world, asteroids.0);
+                }
                 next_state.set(GameState::Playing);
             }
+            // Cargo ships arrive with their convoys, see `spawn_docked_convoys`.
+            // Locked hideouts only offer to sell the player a way in.
             (_, _, Some(_)) => {
                 next_state.set(GameState::Home);
             }
@@ -1,10 +1,10 @@
 use std::f32::consts::PI;
 
-use bevy::sprite::{MaterialMesh2dBundle, Mesh2dHandle};
+use crate::{bullets::spawn_laser, prelude::*};
 
-use crate::prelude::*;
+pub const LASER_SPEED: f32 = 1000.0;
 
-pub fn get_turret_location(spine: &Spine, turret_name: &'static str) -> Vec2 {
+pub fn get_turret_location(spine: &Spine, turret_name: &str) -> Vec2 {
     if let Some(bone) = spine.skeleton.find_bone(turret_name) {
         // "world" is relative to the skeleton, not the whole world.
         bone.world_position().into()
@@ -13,7 +13,7 @@ pub fn get_turret_location(spine: &Spine, turret_name: &'static str) -> Vec2 {
     }
 }
 
-pub fn get_turret_rotation(spine: &Spine, turret_name: &'static str) -> f32 {
+pub fn get_turret_rotation(spine: &Spine, turret_name: &str) -> f32 {
     if let Some(bone) = spine.skeleton.find_bone(turret_name) {
         bone.rotation() * PI / 180.0
     } else {
@@ -21,7 +21,7 @@ pub fn get_turret_rotation(spine: &Spine, turret_name: &'static str) -> f32 {
     }
 }
 
-pub fn rotate_turret(spine: &mut Spine, turret_name: &'static str, rotation: f32) {
+pub fn rotate_turret(spine: &mut Spine, turret_name: &str, rotation: f32) {
     if let Some(mut bone) = spine.skeleton.find_bone_mut(turret_name) {
         bone.set_rotation(rotation * 180.0 / PI);
     }
@@ -29,7 +29,7 @@ pub fn rotate_turret(spine: &mut Spine, turret_name: &'static str, rotation: f32
 
 pub fn rotate_towards_world_location(
     spine: &mut Spine,
-    turret_name: &'static str,
+    turret_name: &str,
     location: &Transform,
     mouse_world_location: Vec2,
     my_inertia: &InertiaVolume,
@@ -43,38 +43,46 @@ pub fn rotate_towards_world_location(
 }
 
 pub fn fire_laser_from_turret(
-    turret_name: &'static str,
+    turret_name: &str,
     spine: &Spine,
     location: &Transform,
     my_inertia: &InertiaVolume,
     commands: &mut Commands<'_, '_>,
-    mesh: Mesh2dHandle,
-    material: Handle<ColorMaterial>,
     bullet: Bullet,
+    damage: Damage,
 ) {
-    // Build a transform for the bullet.
-    let mut transform = Transform::from_xyz(0.0, 0.0, 0.0);
     // Shoot from the appropriate turret.
     let local_turret_location = get_turret_location(&spine, turret_name);
-    transform.translation = location.transform_point(local_turret_location.extend(10.0));
+    let turret_location = location.transform_point(local_turret_location.extend(10.0));
     // Shoot in the appropriate direction.
     let turret_rotation = get_turret_rotation(&spine, turret_name);
     let total_rotation = turret_rotation + my_inertia.rotation();
-    let direction = Vec2::new(f32::cos(total_rotation), f32::sin(total_rotation));
-    transform.rotation = Quat::from_rotation_z(total_rotation);
+    fire_laser_from_hardpoint(
+        turret_location,
+        total_rotation,
+        my_inertia,
+        commands,
+        bullet,
+        damage,
+    );
+}
+
+/// Fires from a fixed point in world space, for ships without a skeleton.
+pub fn fire_laser_from_hardpoint(
+    translation: Vec3,
+    rotation: f32,
+    my_inertia: &InertiaVolume,
+    commands: &mut Commands<'_, '_>,
+    bullet: Bullet,
+    damage: Damage,
+) {
+    // Build a transform for the bullet.
+    let mut transform = Transform::from_translation(translation);
+    let direction = Vec2::new(f32::cos(rotation), f32::sin(rotation));
+    transform.rotation = Quat::from_rotation_z(rotation);
     // Give it some speed!
     let mut inertia = InertiaVolume::new(1.0, 1.0);
-    inertia.velocity = my_inertia.velocity + direction * 1000.0;
+    inertia.velocity = my_inertia.velocity + direction * LASER_SPEED;
 
-    commands.spawn((
-        MaterialMesh2dBundle {
-            mesh,
-            material,
-            transform,
-            ..Default::default()
-        },
-        inertia,
-        bullet,
-        Regional,
-    ));
+    spawn_laser(commands, transform, inertia, bullet, damage);
 }
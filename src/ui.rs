@@ -1,11 +1,10 @@
 use bevy::text::DEFAULT_FONT_HANDLE;
 
-use crate::{home::Career, prelude::*};
+use crate::{heat::Heat, home::Career, prelude::*};
 
-const CARGO_CELL_COUNT: usize = 100;
 const CARGO_CELL_COLUMNS: usize = 20;
-const CARGO_CELL_ROWS: usize = CARGO_CELL_COUNT / CARGO_CELL_COLUMNS;
 const CARGO_CELL_SIZE: f32 = 16.;
+const CARGO_GRID_TOP: f32 = 90.;
 
 pub struct GameUiPlugin;
 
@@ -13,11 +12,11 @@ pub struct GameUiPlugin;
 pub struct UiState {
     shield_display: Entity,
     hull_display: Entity,
+    heat_display: Entity,
     cargo_display: Entity,
     cargo_text: Entity,
     cargo_cells: Vec<Entity>,
     upgrade_text: Entity,
-    pub home_text: Entity,
     pub central_text: Entity,
     pub game_over_text: Entity,
     pub retire_text: Entity,
@@ -28,7 +27,9 @@ impl Plugin for GameUiPlugin {
         app.add_systems(OnExit(GameState::Loading), setup_ui)
             .add_systems(
                 PostUpdate,
-                update_ui.run_if(not(in_state(GameState::Loading))),
+                (resize_cargo_grid, update_ui)
+                    .chain()
+                    .run_if(not(in_state(GameState::Loading))),
             );
     }
 }
@@ -126,19 +127,40 @@ fn setup_ui(mut commands: Commands, game_assets: Res<GameAssets>) {
             ..Default::default()
         },))
         .id();
-    // Display cargo!
-    let mut cargo_cells = Vec::new();
+    let heat_display = commands
+        .spawn((TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                // Top right, out of the way of the cargo hold.
+                right: Val::Px(0.),
+                top: Val::Px(0.),
+                width: Val::Px(230.),
+                height: Val::Px(20.),
+                ..Default::default()
+            },
+            background_color: Color::rgba(0., 0., 0., 1.0).into(),
+            text: Text::from_section(
+                "Heat: 0%",
+                TextStyle {
+                    font: DEFAULT_FONT_HANDLE.typed(),
+                    font_size: 20.,
+                    color: Color::WHITE,
+                },
+            )
+            .with_alignment(TextAlignment::Center),
+            ..Default::default()
+        },))
+        .id();
+    // Display cargo! Cells are added once we know how big the hold is.
     let mut cargo_text = None;
     let cargo_display = commands
         .spawn((NodeBundle {
             style: Style {
                 position_type: PositionType::Absolute,
                 display: Display::Grid,
-                grid_template_rows: RepeatedGridTrack::flex(CARGO_CELL_ROWS as u16, 1.),
                 grid_template_columns: RepeatedGridTrack::flex(CARGO_CELL_COLUMNS as u16, 1.),
                 left: Val::Px(0.),
-                top: Val::Px(90.),
-                height: Val::Px(CARGO_CELL_SIZE * CARGO_CELL_ROWS as f32),
+                top: Val::Px(CARGO_GRID_TOP),
                 width: Val::Px(CARGO_CELL_SIZE * CARGO_CELL_COLUMNS as f32),
                 ..Default::default()
             },
@@ -189,24 +211,6 @@ fn setup_ui(mut commands: Commands, game_assets: Res<GameAssets>) {
                     })
                     .id(),
             );
-            for _ in 0..CARGO_CELL_COUNT {
-                cargo_cells.push(
-                    builder
-                        .spawn(NodeBundle {
-                            border_color: Color::rgba(0.0, 0.0, 0.0, 1.0).into(),
-                            style: Style {
-                                display: Display::Grid,
-                                width: Val::Px(CARGO_CELL_SIZE),
-                                height: Val::Px(CARGO_CELL_SIZE),
-                                border: UiRect::all(Val::Px(1.0)),
-                                ..Default::default()
-                            },
-                            background_color: Color::rgba(0.0, 0.0, 0.0, 1.0).into(),
-                            ..Default::default()
-                        })
-                        .id(),
-                );
-            }
         })
         .id();
     let upgrade_text = commands
@@ -214,7 +218,7 @@ fn setup_ui(mut commands: Commands, game_assets: Res<GameAssets>) {
             style: Style {
                 position_type: PositionType::Absolute,
                 left: Val::Px(0.),
-                top: Val::Px(170.),
+                top: Val::Px(CARGO_GRID_TOP),
                 width: Val::Px(400.),
                 height: Val::Px(200.),
                 ..Default::default()
@@ -232,94 +236,6 @@ fn setup_ui(mut commands: Commands, game_assets: Res<GameAssets>) {
         },))
         .id();
     // End cargo.
-    // Home text.
-    let mut home_text = None;
-    commands
-        .spawn(NodeBundle {
-            style: Style {
-                position_type: PositionType::Absolute,
-                display: Display::Flex,
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                width: Val::Percent(100.),
-                height: Val::Percent(100.),
-                ..Default::default()
-            },
-            ..Default::default()
-        })
-        .with_children(|builder| {
-            home_text = Some(
-                builder
-                    .spawn(TextBundle {
-                        background_color: Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
-                        style: Style {
-                            ..Default::default()
-                        },
-                        text: Text::from_sections(vec![
-                            TextSection {
-                                value: "Earnings: ".to_string(),
-                                style: TextStyle {
-                                    font: DEFAULT_FONT_HANDLE.typed(),
-                                    font_size: 20.,
-                                    color: Color::WHITE,
-                                },
-                            },
-                            TextSection {
-                                value: "XXX".to_string(),
-                                style: TextStyle {
-                                    font: DEFAULT_FONT_HANDLE.typed(),
-                                    font_size: 20.,
-                                    color: Color::WHITE,
-                                },
-                            },
-                            TextSection {
-                                value: "\nRepair costs: ".to_string(),
-                                style: TextStyle {
-                                    font: DEFAULT_FONT_HANDLE.typed(),
-                                    font_size: 20.,
-                                    color: Color::WHITE,
-                                },
-                            },
-                            TextSection {
-                                value: "XXX".to_string(),
-                                style: TextStyle {
-                                    font: DEFAULT_FONT_HANDLE.typed(),
-                                    font_size: 20.,
-                                    color: Color::WHITE,
-                                },
-                            },
-                            TextSection {
-                                value: "\nUpgrade discovered: ".to_string(),
-                                style: TextStyle {
-                                    font: DEFAULT_FONT_HANDLE.typed(),
-                                    font_size: 20.,
-                                    color: Color::WHITE,
-                                },
-                            },
-                            TextSection {
-                                value: "\n10 days left".to_string(),
-                                style: TextStyle {
-                                    font: DEFAULT_FONT_HANDLE.typed(),
-                                    font_size: 20.,
-                                    color: Color::WHITE,
-                                },
-                            },
-                            TextSection {
-                                value: "\nPress <Space> to continue".to_string(),
-                                style: TextStyle {
-                                    font: DEFAULT_FONT_HANDLE.typed(),
-                                    font_size: 20.,
-                                    color: Color::WHITE,
-                                },
-                            },
-                        ])
-                        .with_alignment(TextAlignment::Center),
-                        ..Default::default()
-                    })
-                    .id(),
-            )
-        });
-    // End home text.
     let game_over_text = spawn_centered_text(
         &mut commands,
         vec![
@@ -385,25 +301,77 @@ fn setup_ui(mut commands: Commands, game_assets: Res<GameAssets>) {
     commands.insert_resource(UiState {
         shield_display,
         hull_display,
+        heat_display,
         cargo_display,
         cargo_text: cargo_text.unwrap(),
-        cargo_cells,
+        cargo_cells: Vec::new(),
         upgrade_text,
-        home_text: home_text.unwrap(),
         central_text: central_text.unwrap(),
         game_over_text,
         retire_text,
     });
 }
 
+// Every hull has a different hold, so the grid gets one cell per unit of cargo space.
+fn resize_cargo_grid(
+    mut commands: Commands,
+    mut ui_state: ResMut<UiState>,
+    mut styles: Query<&mut Style>,
+    player: Query<&Player, Changed<Player>>,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    let cell_count = player.max_cargo.ceil().max(0.) as usize;
+    if cell_count == ui_state.cargo_cells.len() {
+        return;
+    }
+    for cell in ui_state.cargo_cells.drain(..) {
+        commands.entity(cell).despawn_recursive();
+    }
+    let cargo_display = ui_state.cargo_display;
+    commands.entity(cargo_display).with_children(|builder| {
+        for _ in 0..cell_count {
+            ui_state.cargo_cells.push(
+                builder
+                    .spawn(NodeBundle {
+                        border_color: Color::rgba(0.0, 0.0, 0.0, 1.0).into(),
+                        style: Style {
+                            display: Display::Grid,
+                            width: Val::Px(CARGO_CELL_SIZE),
+                            height: Val::Px(CARGO_CELL_SIZE),
+                            border: UiRect::all(Val::Px(1.0)),
+                            ..Default::default()
+                        },
+                        background_color: Color::rgba(0.0, 0.0, 0.0, 1.0).into(),
+                        ..Default::default()
+                    })
+                    .id(),
+            );
+        }
+    });
+    let rows = (cell_count + CARGO_CELL_COLUMNS - 1) / CARGO_CELL_COLUMNS;
+    let height = CARGO_CELL_SIZE * rows as f32;
+    if let Ok(mut style) = styles.get_mut(cargo_display) {
+        style.grid_template_rows = RepeatedGridTrack::flex(rows as u16, 1.);
+        style.height = Val::Px(height);
+    }
+    if let Ok(mut style) = styles.get_mut(ui_state.upgrade_text) {
+        // The list of XM found sits just under the grid.
+        style.top = Val::Px(CARGO_GRID_TOP + height);
+    }
+}
+
 fn update_ui(
     game_state: Res<State<GameState>>,
     ui_state: Res<UiState>,
     career: Res<Career>,
+    heat: Res<Heat>,
     mut bg_color: Query<&mut BackgroundColor>,
     mut visibility: Query<&mut Visibility>,
     mut text: Query<&mut Text>,
     player: Query<(&Player, &InertiaVolume, Option<&Jammed>)>,
+    upgrade_catalogue: Res<UpgradeCatalogue>,
 ) {
     if player.is_empty() {
         return;
@@ -424,6 +392,17 @@ fn update_ui(
             bg_color.0 = Color::rgba(hull_percent / 100., 1.0 - hull_percent / 100., 0.0, 1.0);
         }
     }
+    if let Ok(mut heat_text) = text.get_mut(ui_state.heat_display) {
+        let alertness = heat.alertness();
+        heat_text.sections[0].value = format!(
+            "Heat: {}%{}",
+            (alertness * 100.).floor() as i32,
+            if heat.is_hot() { " WANTED" } else { "" }
+        );
+        if let Ok(mut bg_color) = bg_color.get_mut(ui_state.heat_display) {
+            bg_color.0 = Color::rgba(alertness, alertness * 0.3, 0.0, 1.0);
+        }
+    }
     // End health.
 
     // Display cargo!
@@ -461,17 +440,28 @@ fn update_ui(
         cargo_text.sections[1].value = format!(
             "Cargo: {}/{}",
             exotics + salvage + upgrades,
-            CARGO_CELL_COUNT
+            player.max_cargo.ceil() as i32
+        );
+        cargo_text.sections[2].value = format!(
+            " Value: ${}",
+            player.cargo_value(&career.prices()).floor() as i32
         );
-        cargo_text.sections[2].value = format!(" Value: ${}", player.salvage_value.floor() as i32);
     }
     if let Ok(mut upgrade_text) = text.get_mut(ui_state.upgrade_text) {
-        upgrade_text.sections[0].value = "".to_string();
+        upgrade_text.sections[0].value = match player.secondaries.get(player.selected_secondary) {
+            Some(slot) if player.secondaries.len() > 1 => format!(
+                "{}: {} [Right click], [R] to switch",
+                slot.weapon.name(),
+                slot.ammo
+            ),
+            Some(slot) => format!("{}: {} [Right click]", slot.weapon.name(), slot.ammo),
+            None => "".to_string(),
+        };
         for upgrade in player.upgrade_materials.iter() {
             upgrade_text.sections[0].value = format!(
                 "{}\nXM item found: {}",
                 upgrade_text.sections[0].value,
-                upgrade.get_upgrade_material_name()
+                upgrade_catalogue.material_name(upgrade)
             );
         }
     }
@@ -0,0 +1,213 @@
+use rand::distributions::{Distribution, WeightedIndex};
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+/// An XM upgrade material, identified by its id in the upgrade catalogue.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Upgrade(pub String);
+
+/// Every upgrade that can drop, loaded from `assets/upgrades.ron`.
+#[derive(Resource, Debug, Clone, Deserialize)]
+pub struct UpgradeCatalogue {
+    pub upgrades: Vec<UpgradeDefinition>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpgradeDefinition {
+    pub id: String,
+    pub name: String,
+    pub material_name: String,
+    // Index into the `sprites/Upgrades.png` atlas.
+    pub atlas_index: usize,
+    pub drop_weight: f32,
+    /// What the shipyard charges to fit one without the materials.
+    pub price: f32,
+    pub modifiers: Vec<StatModifier>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum StatModifier {
+    Multiply(PlayerStat, f32),
+    Add(PlayerStat, f32),
+    /// Subtracts the amount while the stat is above it, and multiplies by the factor
+    /// after that, so it never reaches zero.
+    Reduce(PlayerStat, f32, f32),
+}
+
+impl StatModifier {
+    pub fn stat(&self) -> PlayerStat {
+        match self {
+            StatModifier::Multiply(stat, _)
+            | StatModifier::Add(stat, _)
+            | StatModifier::Reduce(stat, _, _) => *stat,
+        }
+    }
+
+    pub fn apply(&self, value: f32) -> f32 {
+        match self {
+            StatModifier::Multiply(_, factor) => value * factor,
+            StatModifier::Add(_, amount) => value + amount,
+            StatModifier::Reduce(_, amount, factor) => {
+                if value <= *amount {
+                    value * factor
+                } else {
+                    value - amount
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum PlayerStat {
+    SpeedLimit,
+    EngineStrength,
+    ThrustBrakingStrength,
+    MainSpeed,
+    LaserDamage,
+    LaserPenetration,
+    MaxShields,
+    ShieldRechargeDelay,
+    ShieldRechargeRate,
+    MaxHull,
+    RepairCostPerHull,
+    MaxCargo,
+    JammerRangeMultiplier,
+    JammerCost,
+}
+
+impl PlayerStat {
+    pub const ALL: [PlayerStat; 14] = [
+        PlayerStat::SpeedLimit,
+        PlayerStat::EngineStrength,
+        PlayerStat::ThrustBrakingStrength,
+        PlayerStat::MainSpeed,
+        PlayerStat::LaserDamage,
+        PlayerStat::LaserPenetration,
+        PlayerStat::MaxShields,
+        PlayerStat::ShieldRechargeDelay,
+        PlayerStat::ShieldRechargeRate,
+        PlayerStat::MaxHull,
+        PlayerStat::RepairCostPerHull,
+        PlayerStat::MaxCargo,
+        PlayerStat::JammerRangeMultiplier,
+        PlayerStat::JammerCost,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlayerStat::SpeedLimit => "Top speed",
+            PlayerStat::EngineStrength => "Engine strength",
+            PlayerStat::ThrustBrakingStrength => "Braking strength",
+            PlayerStat::MainSpeed => "Laser cooldown",
+            PlayerStat::LaserDamage => "Laser damage",
+            PlayerStat::LaserPenetration => "Laser penetration",
+            PlayerStat::MaxShields => "Max shields",
+            PlayerStat::ShieldRechargeDelay => "Shield recharge delay",
+            PlayerStat::ShieldRechargeRate => "Shield recharge rate",
+            PlayerStat::MaxHull => "Max hull",
+            PlayerStat::RepairCostPerHull => "Repair cost per hull",
+            PlayerStat::MaxCargo => "Cargo space",
+            PlayerStat::JammerRangeMultiplier => "Jammer range",
+            PlayerStat::JammerCost => "Jammer cost",
+        }
+    }
+}
+
+impl UpgradeCatalogue {
+    pub fn from_ron(contents: &str) -> anyhow::Result<Self> {
+        Ok(ron::from_str(contents)?)
+    }
+
+    pub fn get(&self, upgrade: &Upgrade) -> Option<&UpgradeDefinition> {
+        self.upgrades
+            .iter()
+            .find(|definition| definition.id == upgrade.0)
+    }
+
+    pub fn name(&self, upgrade: &Upgrade) -> String {
+        self.get(upgrade)
+            .map(|definition| definition.name.clone())
+            .unwrap_or_else(|| upgrade.0.clone())
+    }
+
+    pub fn material_name(&self, upgrade: &Upgrade) -> String {
+        self.get(upgrade)
+            .map(|definition| definition.material_name.clone())
+            .unwrap_or_else(|| upgrade.0.clone())
+    }
+
+    pub fn atlas_index(&self, upgrade: &Upgrade) -> usize {
+        self.get(upgrade)
+            .map(|definition| definition.atlas_index)
+            .unwrap_or(0)
+    }
+
+    /// Picks an upgrade according to the drop weights, if anything can drop at all.
+    pub fn random(&self, rng: &mut impl Rng) -> Option<Upgrade> {
+        let weights = WeightedIndex::new(
+            self.upgrades
+                .iter()
+                .map(|definition| definition.drop_weight),
+        )
+        .ok()?;
+        Some(Upgrade(self.upgrades[weights.sample(rng)].id.clone()))
+    }
+}
+
+#[cfg(test)]
+mod upgrades_tests {
+    use super::*;
+
+    fn catalogue() -> UpgradeCatalogue {
+        UpgradeCatalogue::from_ron(include_str!("../assets/upgrades.ron")).unwrap()
+    }
+
+    #[test]
+    fn catalogue_parses() {
+        let catalogue = catalogue();
+        assert!(!catalogue.upgrades.is_empty());
+        for definition in catalogue.upgrades.iter() {
+            assert!(definition.drop_weight >= 0.0, "{}", definition.id);
+            assert!(definition.atlas_index < 8, "{}", definition.id);
+            assert!(definition.price > 0.0, "{}", definition.id);
+        }
+    }
+
+    #[test]
+    fn random_upgrades_exist() {
+        let catalogue = catalogue();
+        let mut rng = GameRng::new(2444);
+        for _ in 0..20 {
+            let upgrade = catalogue.random(&mut rng.loot).unwrap();
+            assert!(catalogue.get(&upgrade).is_some());
+        }
+    }
+
+    #[test]
+    fn hull_upgrade_repairs_the_difference() {
+        let mut player = Player::new();
+        player.hull = 50.0;
+        player.apply_modifier(&StatModifier::Multiply(PlayerStat::MaxHull, 1.1));
+        assert!((player.max_hull - 110.0).abs() < 0.001);
+        assert!((player.hull - 60.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn jammer_efficiency_steps_down_then_tapers() {
+        let catalogue = catalogue();
+        let upgrade = catalogue
+            .get(&Upgrade("JammerEfficiency".to_string()))
+            .unwrap();
+        let mut player = Player::new();
+        player.jammer_cost = 7.0;
+        player.apply_upgrade(upgrade);
+        assert!((player.jammer_cost - 4.0).abs() < 0.001);
+        player.apply_upgrade(upgrade);
+        assert!((player.jammer_cost - 1.0).abs() < 0.001);
+        player.apply_upgrade(upgrade);
+        assert!((player.jammer_cost - 0.9).abs() < 0.001);
+    }
+}